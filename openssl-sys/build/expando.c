@@ -131,6 +131,10 @@ RUST_CONF_OPENSSL_NO_SOCK
 RUST_CONF_OPENSSL_NO_STDIO
 #endif
 
+#ifdef OPENSSL_NO_SM2
+RUST_CONF_OPENSSL_NO_SM2
+#endif
+
 #ifdef OPENSSL_NO_SM3
 RUST_CONF_OPENSSL_NO_SM3
 #endif
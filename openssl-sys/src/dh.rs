@@ -25,8 +25,36 @@ cfg_if! {
                 ptr::null_mut(),
             )
         }
+        pub unsafe fn EVP_PKEY_CTX_set_dh_rfc5114(ctx: *mut EVP_PKEY_CTX, gen: c_int) -> c_int {
+            EVP_PKEY_CTX_ctrl(
+                ctx,
+                EVP_PKEY_DHX,
+                EVP_PKEY_OP_PARAMGEN,
+                EVP_PKEY_CTRL_DH_RFC5114,
+                gen,
+                ptr::null_mut(),
+            )
+        }
     }
 }
 
 pub const EVP_PKEY_CTRL_DH_PARAMGEN_PRIME_LEN: c_int = EVP_PKEY_ALG_CTRL + 1;
 pub const EVP_PKEY_CTRL_DH_PARAMGEN_GENERATOR: c_int = EVP_PKEY_ALG_CTRL + 2;
+pub const EVP_PKEY_CTRL_DH_RFC5114: c_int = EVP_PKEY_ALG_CTRL + 3;
+#[cfg(ossl111)]
+pub const EVP_PKEY_CTRL_DH_PAD: c_int = EVP_PKEY_ALG_CTRL + 16;
+
+#[cfg(all(ossl111, not(ossl300)))]
+pub unsafe fn EVP_PKEY_CTX_set_dh_pad(ctx: *mut EVP_PKEY_CTX, pad: c_int) -> c_int {
+    EVP_PKEY_CTX_ctrl(
+        ctx,
+        EVP_PKEY_DH,
+        EVP_PKEY_OP_DERIVE,
+        EVP_PKEY_CTRL_DH_PAD,
+        pad,
+        ptr::null_mut(),
+    )
+}
+
+pub const EVP_PKEY_DH_KDF_NONE: c_int = 1;
+pub const EVP_PKEY_DH_KDF_X9_42: c_int = 2;
@@ -5,6 +5,8 @@ use super::*;
 
 pub const OPENSSL_EC_NAMED_CURVE: c_int = 1;
 
+pub const EC_R_INVALID_LENGTH: c_int = 117;
+
 cfg_if! {
     if #[cfg(not(ossl300))] {
         pub unsafe fn EVP_PKEY_CTX_set_ec_paramgen_curve_nid(ctx: *mut EVP_PKEY_CTX, nid: c_int) -> c_int {
@@ -17,6 +19,17 @@ cfg_if! {
                 ptr::null_mut(),
             )
         }
+
+        pub unsafe fn EVP_PKEY_CTX_set_ec_param_enc(ctx: *mut EVP_PKEY_CTX, param_enc: c_int) -> c_int {
+            EVP_PKEY_CTX_ctrl(
+                ctx,
+                EVP_PKEY_EC,
+                EVP_PKEY_OP_PARAMGEN|EVP_PKEY_OP_KEYGEN,
+                EVP_PKEY_CTRL_EC_PARAM_ENC,
+                param_enc,
+                ptr::null_mut(),
+            )
+        }
     }
 }
 #[cfg(ossl300)]
@@ -30,3 +43,4 @@ pub unsafe fn EVP_EC_gen(curve: *const c_char) -> *mut EVP_PKEY {
 }
 
 pub const EVP_PKEY_CTRL_EC_PARAMGEN_CURVE_NID: c_int = EVP_PKEY_ALG_CTRL + 1;
+pub const EVP_PKEY_CTRL_EC_PARAM_ENC: c_int = EVP_PKEY_ALG_CTRL + 2;
@@ -6,6 +6,13 @@ pub const EVP_MAX_MD_SIZE: c_uint = 64;
 pub const PKCS5_SALT_LEN: c_int = 8;
 pub const PKCS12_DEFAULT_ITER: c_int = 2048;
 
+pub const EVP_R_EXPECTING_AN_RSA_KEY: c_int = 127;
+pub const EVP_R_EXPECTING_A_EC_KEY: c_int = 142;
+pub const EVP_R_INVALID_KEY_LENGTH: c_int = 130;
+pub const EVP_R_OPERATION_NOT_SUPPORTED_FOR_THIS_KEYTYPE: c_int = 150;
+pub const EVP_R_NO_KEY_SET: c_int = 154;
+pub const EVP_R_UNKNOWN_DIGEST: c_int = 161;
+
 pub const EVP_PKEY_RSA: c_int = NID_rsaEncryption;
 #[cfg(any(ossl111, libressl, boringssl, awslc))]
 pub const EVP_PKEY_RSA_PSS: c_int = NID_rsassaPss;
@@ -30,6 +37,10 @@ pub const EVP_PKEY_CMAC: c_int = NID_cmac;
 pub const EVP_PKEY_POLY1305: c_int = NID_poly1305;
 #[cfg(any(ossl110, libressl360))]
 pub const EVP_PKEY_HKDF: c_int = NID_hkdf;
+#[cfg(ossl110)]
+pub const EVP_PKEY_SCRYPT: c_int = NID_id_scrypt;
+#[cfg(ossl110)]
+pub const EVP_PKEY_TLS1_PRF: c_int = NID_tls1_prf;
 
 #[cfg(ossl110)]
 pub const EVP_CIPHER_CTX_FLAG_WRAP_ALLOW: c_int = 0x1;
@@ -228,6 +239,8 @@ pub const EVP_PKEY_CTRL_SET_MAC_KEY: c_int = 6;
 
 pub const EVP_PKEY_CTRL_CIPHER: c_int = 12;
 
+pub const EVP_PKEY_CTRL_GET_MD: c_int = 13;
+
 pub const EVP_PKEY_ALG_CTRL: c_int = 0x1000;
 
 #[cfg(any(ossl111, libressl360))]
@@ -239,6 +252,15 @@ pub const EVP_PKEY_HKDEF_MODE_EXTRACT_ONLY: c_int = 1;
 #[cfg(any(ossl111, libressl360))]
 pub const EVP_PKEY_HKDEF_MODE_EXPAND_ONLY: c_int = 2;
 
+#[cfg(ossl110)]
+pub const EVP_PKEY_CTRL_TLS_MD: c_int = EVP_PKEY_ALG_CTRL;
+
+#[cfg(ossl110)]
+pub const EVP_PKEY_CTRL_TLS_SECRET: c_int = EVP_PKEY_ALG_CTRL + 1;
+
+#[cfg(ossl110)]
+pub const EVP_PKEY_CTRL_TLS_SEED: c_int = EVP_PKEY_ALG_CTRL + 2;
+
 #[cfg(any(ossl110, libressl360))]
 pub const EVP_PKEY_CTRL_HKDF_MD: c_int = EVP_PKEY_ALG_CTRL + 3;
 
@@ -254,6 +276,24 @@ pub const EVP_PKEY_CTRL_HKDF_INFO: c_int = EVP_PKEY_ALG_CTRL + 6;
 #[cfg(any(ossl111, libressl360))]
 pub const EVP_PKEY_CTRL_HKDF_MODE: c_int = EVP_PKEY_ALG_CTRL + 7;
 
+#[cfg(ossl110)]
+pub const EVP_PKEY_CTRL_PASS: c_int = EVP_PKEY_ALG_CTRL + 8;
+
+#[cfg(ossl110)]
+pub const EVP_PKEY_CTRL_SCRYPT_SALT: c_int = EVP_PKEY_ALG_CTRL + 9;
+
+#[cfg(ossl110)]
+pub const EVP_PKEY_CTRL_SCRYPT_N: c_int = EVP_PKEY_ALG_CTRL + 10;
+
+#[cfg(ossl110)]
+pub const EVP_PKEY_CTRL_SCRYPT_R: c_int = EVP_PKEY_ALG_CTRL + 11;
+
+#[cfg(ossl110)]
+pub const EVP_PKEY_CTRL_SCRYPT_P: c_int = EVP_PKEY_ALG_CTRL + 12;
+
+#[cfg(ossl110)]
+pub const EVP_PKEY_CTRL_SCRYPT_MAXMEM_BYTES: c_int = EVP_PKEY_ALG_CTRL + 13;
+
 #[cfg(any(all(ossl111, not(ossl300)), libressl360))]
 pub unsafe fn EVP_PKEY_CTX_set_hkdf_mode(ctx: *mut EVP_PKEY_CTX, mode: c_int) -> c_int {
     EVP_PKEY_CTX_ctrl(
@@ -326,6 +366,111 @@ pub unsafe fn EVP_PKEY_CTX_add1_hkdf_info(
     )
 }
 
+#[cfg(all(ossl110, not(ossl300)))]
+pub unsafe fn EVP_PKEY_CTX_set_tls1_prf_md(ctx: *mut EVP_PKEY_CTX, md: *const EVP_MD) -> c_int {
+    EVP_PKEY_CTX_ctrl(
+        ctx,
+        -1,
+        EVP_PKEY_OP_DERIVE,
+        EVP_PKEY_CTRL_TLS_MD,
+        0,
+        md as *mut c_void,
+    )
+}
+
+#[cfg(all(ossl110, not(ossl300)))]
+pub unsafe fn EVP_PKEY_CTX_set1_tls1_prf_secret(
+    ctx: *mut EVP_PKEY_CTX,
+    sec: *const u8,
+    seclen: c_int,
+) -> c_int {
+    EVP_PKEY_CTX_ctrl(
+        ctx,
+        -1,
+        EVP_PKEY_OP_DERIVE,
+        EVP_PKEY_CTRL_TLS_SECRET,
+        seclen,
+        sec as *mut c_void,
+    )
+}
+
+#[cfg(all(ossl110, not(ossl300)))]
+pub unsafe fn EVP_PKEY_CTX_add1_tls1_prf_seed(
+    ctx: *mut EVP_PKEY_CTX,
+    seed: *const u8,
+    seedlen: c_int,
+) -> c_int {
+    EVP_PKEY_CTX_ctrl(
+        ctx,
+        -1,
+        EVP_PKEY_OP_DERIVE,
+        EVP_PKEY_CTRL_TLS_SEED,
+        seedlen,
+        seed as *mut c_void,
+    )
+}
+
+#[cfg(all(ossl110, not(ossl300)))]
+pub unsafe fn EVP_PKEY_CTX_set1_pbe_pass(
+    ctx: *mut EVP_PKEY_CTX,
+    pass: *const c_char,
+    passlen: c_int,
+) -> c_int {
+    EVP_PKEY_CTX_ctrl(
+        ctx,
+        -1,
+        EVP_PKEY_OP_DERIVE,
+        EVP_PKEY_CTRL_PASS,
+        passlen,
+        pass as *mut c_void,
+    )
+}
+
+#[cfg(all(ossl110, not(ossl300)))]
+pub unsafe fn EVP_PKEY_CTX_set1_scrypt_salt(
+    ctx: *mut EVP_PKEY_CTX,
+    salt: *const u8,
+    saltlen: c_int,
+) -> c_int {
+    EVP_PKEY_CTX_ctrl(
+        ctx,
+        -1,
+        EVP_PKEY_OP_DERIVE,
+        EVP_PKEY_CTRL_SCRYPT_SALT,
+        saltlen,
+        salt as *mut c_void,
+    )
+}
+
+#[cfg(all(ossl110, not(ossl300)))]
+pub unsafe fn EVP_PKEY_CTX_set_scrypt_N(ctx: *mut EVP_PKEY_CTX, n: u64) -> c_int {
+    EVP_PKEY_CTX_ctrl_uint64(ctx, -1, EVP_PKEY_OP_DERIVE, EVP_PKEY_CTRL_SCRYPT_N, n)
+}
+
+#[cfg(all(ossl110, not(ossl300)))]
+pub unsafe fn EVP_PKEY_CTX_set_scrypt_r(ctx: *mut EVP_PKEY_CTX, r: u64) -> c_int {
+    EVP_PKEY_CTX_ctrl_uint64(ctx, -1, EVP_PKEY_OP_DERIVE, EVP_PKEY_CTRL_SCRYPT_R, r)
+}
+
+#[cfg(all(ossl110, not(ossl300)))]
+pub unsafe fn EVP_PKEY_CTX_set_scrypt_p(ctx: *mut EVP_PKEY_CTX, p: u64) -> c_int {
+    EVP_PKEY_CTX_ctrl_uint64(ctx, -1, EVP_PKEY_OP_DERIVE, EVP_PKEY_CTRL_SCRYPT_P, p)
+}
+
+#[cfg(all(ossl110, not(ossl300)))]
+pub unsafe fn EVP_PKEY_CTX_set_scrypt_maxmem_bytes(
+    ctx: *mut EVP_PKEY_CTX,
+    maxmem_bytes: u64,
+) -> c_int {
+    EVP_PKEY_CTX_ctrl_uint64(
+        ctx,
+        -1,
+        EVP_PKEY_OP_DERIVE,
+        EVP_PKEY_CTRL_SCRYPT_MAXMEM_BYTES,
+        maxmem_bytes,
+    )
+}
+
 #[cfg(not(any(ossl300, boringssl, awslc)))]
 pub unsafe fn EVP_PKEY_CTX_set_signature_md(cxt: *mut EVP_PKEY_CTX, md: *mut EVP_MD) -> c_int {
     EVP_PKEY_CTX_ctrl(
@@ -338,6 +483,21 @@ pub unsafe fn EVP_PKEY_CTX_set_signature_md(cxt: *mut EVP_PKEY_CTX, md: *mut EVP
     )
 }
 
+#[cfg(not(any(ossl300, boringssl, awslc)))]
+pub unsafe fn EVP_PKEY_CTX_get_signature_md(
+    cxt: *mut EVP_PKEY_CTX,
+    md: *mut *const EVP_MD,
+) -> c_int {
+    EVP_PKEY_CTX_ctrl(
+        cxt,
+        -1,
+        EVP_PKEY_OP_TYPE_SIG,
+        EVP_PKEY_CTRL_GET_MD,
+        0,
+        md as *mut c_void,
+    )
+}
+
 #[cfg(not(osslconf = "OPENSSL_NO_DEPRECATED_3_0"))]
 pub unsafe fn EVP_PKEY_assign_RSA(pkey: *mut EVP_PKEY, rsa: *mut RSA) -> c_int {
     EVP_PKEY_assign(pkey, EVP_PKEY_RSA, rsa as *mut c_void)
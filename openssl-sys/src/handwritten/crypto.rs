@@ -67,6 +67,7 @@ extern "C" {
     pub fn FIPS_mode_set(onoff: c_int) -> c_int;
 
     pub fn CRYPTO_memcmp(a: *const c_void, b: *const c_void, len: size_t) -> c_int;
+    pub fn OPENSSL_cleanse(ptr: *mut c_void, len: size_t);
 
     #[cfg(ossl300)]
     pub fn OSSL_LIB_CTX_new() -> *mut OSSL_LIB_CTX;
@@ -4,6 +4,17 @@ use super::super::*;
 extern "C" {
     pub fn EVP_PKEY_CTX_set_dh_paramgen_prime_len(ctx: *mut EVP_PKEY_CTX, len: c_int) -> c_int;
     pub fn EVP_PKEY_CTX_set_dh_paramgen_generator(ctx: *mut EVP_PKEY_CTX, gen: c_int) -> c_int;
+    pub fn EVP_PKEY_CTX_set_dh_rfc5114(ctx: *mut EVP_PKEY_CTX, gen: c_int) -> c_int;
+    pub fn EVP_PKEY_CTX_set_dh_pad(ctx: *mut EVP_PKEY_CTX, pad: c_int) -> c_int;
+    pub fn EVP_PKEY_CTX_set_dh_kdf_type(ctx: *mut EVP_PKEY_CTX, kdf: c_int) -> c_int;
+    pub fn EVP_PKEY_CTX_set_dh_kdf_md(ctx: *mut EVP_PKEY_CTX, md: *const EVP_MD) -> c_int;
+    pub fn EVP_PKEY_CTX_set_dh_kdf_outlen(ctx: *mut EVP_PKEY_CTX, len: c_int) -> c_int;
+    pub fn EVP_PKEY_CTX_set0_dh_kdf_ukm(
+        ctx: *mut EVP_PKEY_CTX,
+        ukm: *mut c_uchar,
+        len: c_int,
+    ) -> c_int;
+    pub fn EVP_PKEY_CTX_set0_dh_kdf_oid(ctx: *mut EVP_PKEY_CTX, oid: *mut ASN1_OBJECT) -> c_int;
 }
 
 #[cfg(not(osslconf = "OPENSSL_NO_DEPRECATED_3_0"))]
@@ -4,6 +4,7 @@ use libc::*;
 #[cfg(ossl300)]
 extern "C" {
     pub fn EVP_PKEY_CTX_set_ec_paramgen_curve_nid(ctx: *mut EVP_PKEY_CTX, nid: c_int) -> c_int;
+    pub fn EVP_PKEY_CTX_set_ec_param_enc(ctx: *mut EVP_PKEY_CTX, param_enc: c_int) -> c_int;
 }
 
 #[repr(C)]
@@ -19,7 +20,16 @@ pub enum EC_METHOD {}
 pub enum EC_GROUP {}
 pub enum EC_POINT {}
 
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct EC_builtin_curve {
+    pub nid: c_int,
+    pub comment: *const c_char,
+}
+
 extern "C" {
+    pub fn EC_get_builtin_curves(r: *mut EC_builtin_curve, nitems: size_t) -> size_t;
+
     #[cfg(not(any(libressl410, osslconf = "OPENSSL_NO_DEPRECATED_3_0")))]
     pub fn EC_GROUP_new(meth: *const EC_METHOD) -> *mut EC_GROUP;
 
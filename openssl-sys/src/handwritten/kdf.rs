@@ -21,6 +21,34 @@ cfg_if! {
                 info: *const u8,
                 infolen: c_int,
             ) -> c_int;
+            pub fn EVP_PKEY_CTX_set_tls1_prf_md(ctx: *mut EVP_PKEY_CTX, md: *const EVP_MD) -> c_int;
+            pub fn EVP_PKEY_CTX_set1_tls1_prf_secret(
+                ctx: *mut EVP_PKEY_CTX,
+                sec: *const u8,
+                seclen: c_int,
+            ) -> c_int;
+            pub fn EVP_PKEY_CTX_add1_tls1_prf_seed(
+                ctx: *mut EVP_PKEY_CTX,
+                seed: *const u8,
+                seedlen: c_int,
+            ) -> c_int;
+            pub fn EVP_PKEY_CTX_set1_pbe_pass(
+                ctx: *mut EVP_PKEY_CTX,
+                pass: *const c_char,
+                passlen: c_int,
+            ) -> c_int;
+            pub fn EVP_PKEY_CTX_set1_scrypt_salt(
+                ctx: *mut EVP_PKEY_CTX,
+                salt: *const u8,
+                saltlen: c_int,
+            ) -> c_int;
+            pub fn EVP_PKEY_CTX_set_scrypt_N(ctx: *mut EVP_PKEY_CTX, n: u64) -> c_int;
+            pub fn EVP_PKEY_CTX_set_scrypt_r(ctx: *mut EVP_PKEY_CTX, r: u64) -> c_int;
+            pub fn EVP_PKEY_CTX_set_scrypt_p(ctx: *mut EVP_PKEY_CTX, p: u64) -> c_int;
+            pub fn EVP_PKEY_CTX_set_scrypt_maxmem_bytes(
+                ctx: *mut EVP_PKEY_CTX,
+                maxmem_bytes: u64,
+            ) -> c_int;
             pub fn EVP_KDF_CTX_new(kdf: *mut EVP_KDF) -> *mut EVP_KDF_CTX;
             pub fn EVP_KDF_CTX_free(ctx: *mut EVP_KDF_CTX);
             pub fn EVP_KDF_CTX_reset(ctx: *mut EVP_KDF_CTX);
@@ -13,6 +13,10 @@ extern "C" {
     #[cfg(ossl300)]
     pub fn OSSL_PROVIDER_unload(prov: *mut OSSL_PROVIDER) -> c_int;
     #[cfg(ossl300)]
+    pub fn OSSL_PROVIDER_available(ctx: *mut OSSL_LIB_CTX, name: *const c_char) -> c_int;
+    #[cfg(ossl300)]
+    pub fn OSSL_PROVIDER_get0_name(prov: *const OSSL_PROVIDER) -> *const c_char;
+    #[cfg(ossl300)]
     pub fn OSSL_PROVIDER_set_default_search_path(
         ctx: *mut OSSL_LIB_CTX,
         path: *const c_char,
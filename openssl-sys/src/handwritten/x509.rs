@@ -53,6 +53,7 @@ extern "C" {
     pub fn X509_verify_cert_error_string(n: c_long) -> *const c_char;
 
     pub fn X509_sign(x: *mut X509, pkey: *mut EVP_PKEY, md: *const EVP_MD) -> c_int;
+    pub fn X509_sign_ctx(x: *mut X509, ctx: *mut EVP_MD_CTX) -> c_int;
 
     pub fn X509_digest(
         x: *const X509,
@@ -292,6 +293,8 @@ extern "C" {
 }
 extern "C" {
     pub fn X509_set_pubkey(x: *mut X509, pkey: *mut EVP_PKEY) -> c_int;
+    #[cfg(all(ossl300, not(osslconf = "OPENSSL_NO_SM2")))]
+    pub fn X509_set0_distinguishing_id(x: *mut X509, d_id: *mut ASN1_OCTET_STRING);
     pub fn X509_REQ_verify(req: *mut X509_REQ, pkey: *mut EVP_PKEY) -> c_int;
     pub fn X509_getm_notBefore(x: *const X509) -> *mut ASN1_TIME;
     pub fn X509_getm_notAfter(x: *const X509) -> *mut ASN1_TIME;
@@ -967,6 +967,10 @@ pub const NID_X25519: c_int = 950;
 #[cfg(ossl111)]
 pub const NID_X448: c_int = 1035;
 #[cfg(ossl110)]
+pub const NID_id_scrypt: c_int = 973;
+#[cfg(ossl110)]
+pub const NID_tls1_prf: c_int = 1021;
+#[cfg(ossl110)]
 pub const NID_hkdf: c_int = 1036;
 #[cfg(libressl360)]
 pub const NID_hkdf: c_int = 1022;
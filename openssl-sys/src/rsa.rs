@@ -59,6 +59,17 @@ cfg_if! {
             )
         }
 
+        pub unsafe fn EVP_PKEY_CTX_get_rsa_pss_saltlen(ctx: *mut EVP_PKEY_CTX, len: *mut c_int) -> c_int {
+            EVP_PKEY_CTX_ctrl(
+                ctx,
+                EVP_PKEY_RSA,
+                EVP_PKEY_OP_SIGN | EVP_PKEY_OP_VERIFY,
+                EVP_PKEY_CTRL_GET_RSA_PSS_SALTLEN,
+                0,
+                len as *mut c_void,
+            )
+        }
+
         pub unsafe fn EVP_PKEY_CTX_set_rsa_mgf1_md(ctx: *mut EVP_PKEY_CTX, md: *mut EVP_MD) -> c_int {
             EVP_PKEY_CTX_ctrl(
                 ctx,
@@ -69,6 +80,28 @@ cfg_if! {
                 md as *mut c_void,
             )
         }
+
+        pub unsafe fn EVP_PKEY_CTX_get_rsa_mgf1_md(ctx: *mut EVP_PKEY_CTX, md: *mut *const EVP_MD) -> c_int {
+            EVP_PKEY_CTX_ctrl(
+                ctx,
+                EVP_PKEY_RSA,
+                EVP_PKEY_OP_TYPE_SIG | EVP_PKEY_OP_TYPE_CRYPT,
+                EVP_PKEY_CTRL_GET_RSA_MGF1_MD,
+                0,
+                md as *mut c_void,
+            )
+        }
+
+        pub unsafe fn EVP_PKEY_CTX_get_rsa_oaep_md(ctx: *mut EVP_PKEY_CTX, md: *mut *const EVP_MD) -> c_int {
+            EVP_PKEY_CTX_ctrl(
+                ctx,
+                EVP_PKEY_RSA,
+                EVP_PKEY_OP_TYPE_CRYPT,
+                EVP_PKEY_CTRL_GET_RSA_OAEP_MD,
+                0,
+                md as *mut c_void,
+            )
+        }
     }
 }
 
@@ -98,6 +131,20 @@ pub unsafe fn EVP_PKEY_CTX_set0_rsa_oaep_label(
     )
 }
 
+pub unsafe fn EVP_PKEY_CTX_get0_rsa_oaep_label(
+    ctx: *mut EVP_PKEY_CTX,
+    label: *mut *mut c_uchar,
+) -> c_int {
+    EVP_PKEY_CTX_ctrl(
+        ctx,
+        EVP_PKEY_RSA,
+        EVP_PKEY_OP_TYPE_CRYPT,
+        EVP_PKEY_CTRL_GET_RSA_OAEP_LABEL,
+        0,
+        label as *mut c_void,
+    )
+}
+
 pub const EVP_PKEY_CTRL_RSA_PADDING: c_int = EVP_PKEY_ALG_CTRL + 1;
 pub const EVP_PKEY_CTRL_RSA_PSS_SALTLEN: c_int = EVP_PKEY_ALG_CTRL + 2;
 pub const EVP_PKEY_CTRL_RSA_KEYGEN_BITS: c_int = EVP_PKEY_ALG_CTRL + 3;
@@ -106,9 +153,22 @@ pub const EVP_PKEY_CTRL_RSA_KEYGEN_PUBEXP: c_int = EVP_PKEY_ALG_CTRL + 4;
 pub const EVP_PKEY_CTRL_RSA_MGF1_MD: c_int = EVP_PKEY_ALG_CTRL + 5;
 
 pub const EVP_PKEY_CTRL_GET_RSA_PADDING: c_int = EVP_PKEY_ALG_CTRL + 6;
+pub const EVP_PKEY_CTRL_GET_RSA_PSS_SALTLEN: c_int = EVP_PKEY_ALG_CTRL + 7;
+
+pub const EVP_PKEY_CTRL_GET_RSA_MGF1_MD: c_int = EVP_PKEY_ALG_CTRL + 8;
 
 pub const EVP_PKEY_CTRL_RSA_OAEP_MD: c_int = EVP_PKEY_ALG_CTRL + 9;
 pub const EVP_PKEY_CTRL_RSA_OAEP_LABEL: c_int = EVP_PKEY_ALG_CTRL + 10;
+pub const EVP_PKEY_CTRL_GET_RSA_OAEP_MD: c_int = EVP_PKEY_ALG_CTRL + 11;
+pub const EVP_PKEY_CTRL_GET_RSA_OAEP_LABEL: c_int = EVP_PKEY_ALG_CTRL + 12;
+
+pub const RSA_R_BAD_E_VALUE: c_int = 101;
+pub const RSA_R_DATA_TOO_LARGE: c_int = 109;
+pub const RSA_R_DATA_TOO_LARGE_FOR_KEY_SIZE: c_int = 110;
+pub const RSA_R_DATA_TOO_SMALL_FOR_KEY_SIZE: c_int = 122;
+pub const RSA_R_ILLEGAL_OR_UNSUPPORTED_PADDING_MODE: c_int = 144;
+pub const RSA_R_KEY_SIZE_TOO_SMALL: c_int = 120;
+pub const RSA_R_UNKNOWN_PADDING_TYPE: c_int = 118;
 
 pub const RSA_PKCS1_PADDING: c_int = 1;
 #[cfg(not(ossl300))]
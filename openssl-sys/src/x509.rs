@@ -5,3 +5,6 @@ pub const X509_FILETYPE_ASN1: c_int = 2;
 pub const X509_FILETYPE_DEFAULT: c_int = 3;
 
 pub const ASN1_R_HEADER_TOO_LONG: c_int = 123;
+pub const ASN1_R_ERROR_GETTING_TIME: c_int = 173;
+
+pub const X509_R_UNKNOWN_PURPOSE_ID: c_int = 121;
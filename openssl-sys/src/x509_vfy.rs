@@ -122,6 +122,12 @@ pub const X509_V_FLAG_PARTIAL_CHAIN: c_ulong = 0x80000;
 pub const X509_V_FLAG_NO_ALT_CHAINS: c_ulong = 0x100000;
 pub const X509_V_FLAG_NO_CHECK_TIME: c_ulong = 0x200000;
 
+pub const X509_VP_FLAG_DEFAULT: u32 = 0x1;
+pub const X509_VP_FLAG_OVERWRITE: u32 = 0x2;
+pub const X509_VP_FLAG_RESET_FLAGS: u32 = 0x4;
+pub const X509_VP_FLAG_LOCKED: u32 = 0x8;
+pub const X509_VP_FLAG_ONCE: u32 = 0x10;
+
 pub unsafe fn X509_LOOKUP_add_dir(
     ctx: *mut X509_LOOKUP,
     name: *const c_char,
@@ -136,3 +142,14 @@ pub unsafe fn X509_LOOKUP_add_dir(
         std::ptr::null_mut(),
     )
 }
+
+#[cfg(ossl110)]
+pub unsafe fn X509_STORE_CTX_get_ex_new_index(
+    l: c_long,
+    p: *mut c_void,
+    newf: Option<CRYPTO_EX_new>,
+    dupf: Option<CRYPTO_EX_dup>,
+    freef: Option<CRYPTO_EX_free>,
+) -> c_int {
+    CRYPTO_get_ex_new_index(CRYPTO_EX_INDEX_X509_STORE_CTX, l, p, newf, dupf, freef)
+}
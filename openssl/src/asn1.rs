@@ -743,6 +743,26 @@ impl fmt::Debug for Asn1ObjectRef {
     }
 }
 
+impl fmt::Debug for Asn1Object {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Asn1ObjectRef::fmt(self, fmt)
+    }
+}
+
+impl Eq for Asn1ObjectRef {}
+impl PartialEq for Asn1ObjectRef {
+    fn eq(&self, other: &Asn1ObjectRef) -> bool {
+        unsafe { ffi::OBJ_cmp(self.as_ptr(), other.as_ptr()) == 0 }
+    }
+}
+
+impl Eq for Asn1Object {}
+impl PartialEq for Asn1Object {
+    fn eq(&self, other: &Asn1Object) -> bool {
+        Asn1ObjectRef::eq(self, other)
+    }
+}
+
 use ffi::ASN1_STRING_get0_data;
 
 foreign_type_and_impl_send_sync! {
@@ -18,6 +18,7 @@
 use cfg_if::cfg_if;
 use foreign_types::{ForeignType, ForeignTypeRef};
 use libc::c_int;
+use std::ffi::CStr;
 use std::fmt;
 use std::ptr;
 
@@ -66,7 +67,7 @@ impl PointConversionForm {
 ///
 /// This type acts as a boolean as to whether the `EcGroup` is named or explicit.
 #[derive(Copy, Clone, Debug, PartialEq)]
-pub struct Asn1Flag(c_int);
+pub struct Asn1Flag(pub(crate) c_int);
 
 impl Asn1Flag {
     /// Curve defined using polynomial parameters
@@ -163,6 +164,34 @@ impl EcGroup {
             .map(EcGroup)
         }
     }
+
+    /// Returns the named curves built into OpenSSL, along with a description of each.
+    ///
+    /// This can be used to validate a curve before passing it to
+    /// [`from_curve_name`](Self::from_curve_name) or
+    /// [`PkeyCtxRef::set_ec_paramgen_curve_nid`](crate::pkey_ctx::PkeyCtxRef::set_ec_paramgen_curve_nid).
+    #[corresponds(EC_get_builtin_curves)]
+    pub fn builtin_curves() -> Vec<(Nid, String)> {
+        unsafe {
+            init();
+            let len = ffi::EC_get_builtin_curves(ptr::null_mut(), 0);
+            let mut curves = Vec::with_capacity(len);
+            let len = ffi::EC_get_builtin_curves(curves.as_mut_ptr(), len);
+            curves.set_len(len);
+
+            curves
+                .into_iter()
+                .map(|curve| {
+                    let comment = if curve.comment.is_null() {
+                        String::new()
+                    } else {
+                        CStr::from_ptr(curve.comment).to_string_lossy().into_owned()
+                    };
+                    (Nid::from_raw(curve.nid), comment)
+                })
+                .collect()
+        }
+    }
 }
 
 impl fmt::Debug for EcGroup {
@@ -1110,6 +1139,15 @@ mod test {
         EcKey::generate(&group).unwrap();
     }
 
+    #[test]
+    fn builtin_curves() {
+        let curves = EcGroup::builtin_curves();
+        assert!(curves.iter().any(|(nid, _)| *nid == Nid::X9_62_PRIME256V1));
+        for (nid, _) in curves {
+            EcGroup::from_curve_name(nid).unwrap();
+        }
+    }
+
     #[test]
     fn ec_group_from_components() {
         // parameters are from secp256r1
@@ -54,6 +54,40 @@ impl ErrorStack {
             error.put();
         }
     }
+
+    /// Raises an OpenSSL error with the given library and reason codes and returns it.
+    ///
+    /// This is used when the crate rejects an input itself, so that callers see the same kind of
+    /// error OpenSSL would have reported.
+    pub(crate) fn raise(lib: c_int, reason: c_int) -> ErrorStack {
+        unsafe {
+            ffi::init();
+            raise_error(lib, reason);
+        }
+        ErrorStack::get()
+    }
+}
+
+#[cfg(ossl300)]
+unsafe fn raise_error(lib: c_int, reason: c_int) {
+    ffi::ERR_new();
+    ffi::ERR_set_debug(
+        concat!(file!(), "\0").as_ptr() as _,
+        line!() as _,
+        ptr::null(),
+    );
+    ffi::ERR_set_error(lib, reason, ptr::null());
+}
+
+#[cfg(not(ossl300))]
+unsafe fn raise_error(lib: c_int, reason: c_int) {
+    ffi::ERR_put_error(
+        lib,
+        0,
+        reason,
+        concat!(file!(), "\0").as_ptr() as _,
+        line!() as _,
+    );
 }
 
 impl ErrorStack {
@@ -22,6 +22,34 @@ impl Drop for EvpKdfCtx {
     }
 }
 
+/// A parameter passed to a KDF fetched by name with [`derive_with_named_kdf`].
+///
+/// Each variant holds the parameter name, e.g. `"digest"` or `"secret"`, and its value.
+#[cfg(ossl300)]
+#[derive(Copy, Clone, Debug)]
+pub enum KdfParam<'a> {
+    /// A UTF-8 string parameter.
+    Utf8String(&'a str, &'a str),
+    /// An octet string parameter.
+    OctetString(&'a str, &'a [u8]),
+    /// A signed integer parameter.
+    Int(&'a str, i32),
+    /// An unsigned integer parameter.
+    Uint(&'a str, u32),
+}
+
+/// An Argon2 variant, as defined by RFC 9106.
+#[cfg(all(ossl320, not(osslconf = "OPENSSL_NO_ARGON2")))]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Argon2Variant {
+    /// Argon2d, which uses data-dependent memory access.
+    Argon2d,
+    /// Argon2i, which uses data-independent memory access.
+    Argon2i,
+    /// Argon2id, a hybrid of Argon2d and Argon2i recommended by RFC 9106.
+    Argon2id,
+}
+
 #[cfg(ossl300)]
 #[derive(Copy, Clone)]
 pub enum HkdfMode {
@@ -32,7 +60,7 @@ pub enum HkdfMode {
 
 cfg_if::cfg_if! {
     if #[cfg(ossl300)] {
-        use std::ffi::CStr;
+        use std::ffi::{CStr, CString};
         use std::ptr;
         use foreign_types::ForeignTypeRef;
         use libc::c_char;
@@ -105,6 +133,48 @@ cfg_if::cfg_if! {
             let params = bld.to_param()?;
             kdf_digest(CStr::from_bytes_with_nul(b"HKDF\0").unwrap(), ctx, &params, out)
         }
+
+        /// Derives a key using the KDF named `name`, such as `"SSKDF"` or `"X963KDF"`.
+        ///
+        /// The KDF is fetched from the providers loaded in `ctx`, or the default library context
+        /// if `None`. `params` are passed to the KDF as-is; see the KDF's OpenSSL documentation
+        /// (e.g. `EVP_KDF-X963(7)`) for the parameters it accepts. The derived key fills `out`.
+        ///
+        /// # Panics
+        ///
+        /// Panics if `name` or a parameter name contains a NUL byte.
+        ///
+        /// Requires OpenSSL 3.0.0 or newer.
+        pub fn derive_with_named_kdf(
+            name: &str,
+            params: &[KdfParam<'_>],
+            ctx: Option<&LibCtxRef>,
+            out: &mut [u8],
+        ) -> Result<(), ErrorStack> {
+            let name = CString::new(name).unwrap();
+            let keys = params
+                .iter()
+                .map(|param| {
+                    let (KdfParam::Utf8String(key, _)
+                    | KdfParam::OctetString(key, _)
+                    | KdfParam::Int(key, _)
+                    | KdfParam::Uint(key, _)) = *param;
+                    CString::new(key).unwrap()
+                })
+                .collect::<Vec<_>>();
+
+            let mut bld = OsslParamBuilder::new()?;
+            for (param, key) in params.iter().zip(&keys) {
+                match *param {
+                    KdfParam::Utf8String(_, value) => bld.add_utf8_string(key, value.as_bytes())?,
+                    KdfParam::OctetString(_, value) => bld.add_octet_string(key, value)?,
+                    KdfParam::Int(_, value) => bld.add_int(key, value)?,
+                    KdfParam::Uint(_, value) => bld.add_uint(key, value)?,
+                }
+            }
+            let params = bld.to_param()?;
+            kdf_digest(&name, ctx, &params, out)
+        }
     }
 }
 
@@ -166,6 +236,111 @@ cfg_if::cfg_if! {
             argon2_helper(CStr::from_bytes_with_nul(b"ARGON2ID\0").unwrap(), ctx, pass, salt, ad, secret, iter, lanes, memcost, out)
         }
 
+        /// Typed parameters for deriving a key from a password with Argon2.
+        ///
+        /// The iteration count, memory cost and parallelism default to OpenSSL's defaults of 3
+        /// passes, 1024 KiB and a single lane. A salt must be set before deriving.
+        ///
+        /// Requires OpenSSL 3.2.0 or newer.
+        #[derive(Clone, Debug)]
+        pub struct Argon2<'a> {
+            variant: Argon2Variant,
+            pass: &'a [u8],
+            salt: &'a [u8],
+            ad: Option<&'a [u8]>,
+            secret: Option<&'a [u8]>,
+            iter: u32,
+            lanes: u32,
+            memcost: u32,
+        }
+
+        impl<'a> Argon2<'a> {
+            /// Creates a new set of parameters for `variant`.
+            pub fn new(variant: Argon2Variant) -> Argon2<'a> {
+                Argon2 {
+                    variant,
+                    pass: &[],
+                    salt: &[],
+                    ad: None,
+                    secret: None,
+                    iter: 3,
+                    lanes: 1,
+                    memcost: 1024,
+                }
+            }
+
+            /// Sets the password to derive the key from.
+            pub fn set_password(&mut self, pass: &'a [u8]) -> &mut Argon2<'a> {
+                self.pass = pass;
+                self
+            }
+
+            /// Sets the salt, which must be at least 8 bytes long.
+            pub fn set_salt(&mut self, salt: &'a [u8]) -> &mut Argon2<'a> {
+                self.salt = salt;
+                self
+            }
+
+            /// Sets optional associated data mixed into the derived key.
+            pub fn set_associated_data(&mut self, ad: &'a [u8]) -> &mut Argon2<'a> {
+                self.ad = Some(ad);
+                self
+            }
+
+            /// Sets an optional secret value, sometimes called a pepper.
+            pub fn set_secret(&mut self, secret: &'a [u8]) -> &mut Argon2<'a> {
+                self.secret = Some(secret);
+                self
+            }
+
+            /// Sets the number of passes over memory.
+            pub fn set_iterations(&mut self, iter: u32) -> &mut Argon2<'a> {
+                self.iter = iter;
+                self
+            }
+
+            /// Sets the memory cost in KiB.
+            pub fn set_memory_cost(&mut self, memcost: u32) -> &mut Argon2<'a> {
+                self.memcost = memcost;
+                self
+            }
+
+            /// Sets the degree of parallelism, i.e. the number of lanes.
+            ///
+            /// Lanes are only processed in parallel if a maximum thread count has been set with
+            /// `OSSL_set_max_threads`; the derived key does not depend on it.
+            pub fn set_parallelism(&mut self, lanes: u32) -> &mut Argon2<'a> {
+                self.lanes = lanes;
+                self
+            }
+
+            /// Derives a key filling `out`, using the KDF from `ctx` or the default library
+            /// context if `None`.
+            pub fn derive(
+                &self,
+                ctx: Option<&LibCtxRef>,
+                out: &mut [u8],
+            ) -> Result<(), ErrorStack> {
+                let kdf_identifier = match self.variant {
+                    Argon2Variant::Argon2d => CStr::from_bytes_with_nul(b"ARGON2D\0").unwrap(),
+                    Argon2Variant::Argon2i => CStr::from_bytes_with_nul(b"ARGON2I\0").unwrap(),
+                    Argon2Variant::Argon2id => CStr::from_bytes_with_nul(b"ARGON2ID\0").unwrap(),
+                };
+                argon2_helper(
+                    kdf_identifier,
+                    ctx,
+                    self.pass,
+                    self.salt,
+                    self.ad,
+                    self.secret,
+                    self.iter,
+                    self.lanes,
+                    self.memcost,
+                    out,
+                )
+            }
+        }
+
         /// Derives a key using the argon2* algorithms.
         ///
         /// To use multiple cores to process the lanes in parallel you must
@@ -530,6 +705,36 @@ mod tests {
         assert_eq!(hex::encode(&actual[..]), expected);
     }
 
+    #[test]
+    #[cfg(all(ossl320, not(osslconf = "OPENSSL_NO_ARGON2")))]
+    fn argon2id_typed() {
+        use super::{Argon2, Argon2Variant};
+
+        // RFC 9106 test vector for argon2id
+        let pass = [0x01; 32];
+        let salt = [0x02; 16];
+        let secret = [0x03; 8];
+        let ad = [0x04; 12];
+        let expected = "0d640df58d78766c08c037a34a8b53c9d01ef0452d75b65eb52520e96b01e659";
+
+        let mut argon2 = Argon2::new(Argon2Variant::Argon2id);
+        argon2
+            .set_password(&pass)
+            .set_salt(&salt)
+            .set_secret(&secret)
+            .set_associated_data(&ad)
+            .set_iterations(3)
+            .set_memory_cost(32)
+            .set_parallelism(4);
+        let mut actual = [0u8; 32];
+        argon2.derive(None, &mut actual).unwrap();
+        assert_eq!(hex::encode(actual), expected);
+
+        argon2.set_parallelism(1);
+        argon2.derive(None, &mut actual).unwrap();
+        assert_ne!(hex::encode(actual), expected);
+    }
+
     #[test]
     #[cfg(all(ossl320, not(osslconf = "OPENSSL_NO_ARGON2")))]
     fn argon2id_no_ad_secret() {
@@ -542,4 +747,29 @@ mod tests {
         super::argon2id(None, pass, &salt, None, None, 3, 4, 32, &mut actual).unwrap();
         assert_eq!(hex::encode(&actual[..]), expected);
     }
+
+    #[test]
+    #[cfg(ossl300)]
+    fn x963kdf_by_name() {
+        use super::{derive_with_named_kdf, KdfParam};
+
+        let secret = hex::decode("96c05619d56c328ab95fe84b18264b08725b85e33fd34f08").unwrap();
+        let info = hex::decode("a1b2c3d4e5").unwrap();
+        let params = [
+            KdfParam::Utf8String("digest", "SHA256"),
+            KdfParam::OctetString("secret", &secret),
+            KdfParam::OctetString("info", &info),
+        ];
+        let mut out = [0; 48];
+        derive_with_named_kdf("X963KDF", &params, None, &mut out).unwrap();
+        assert_eq!(
+            hex::encode(out),
+            concat!(
+                "edfbea61cc1924fb5b6c68a7dd6ae0c6a01b867e6178eb7f115b1dd5ac91d986",
+                "cf3ebc6647ddbe8182e4ed709c7e71ce"
+            )
+        );
+
+        assert!(derive_with_named_kdf("NO-SUCH-KDF", &params, None, &mut out).is_err());
+    }
 }
@@ -77,6 +77,17 @@
 //! Additionally, these variables can be prefixed with the upper-cased target architecture (e.g.
 //!     `X86_64_UNKNOWN_LINUX_GNU_OPENSSL_DIR`), which can be useful when cross compiling.
 //!
+//! # Hardening
+//!
+//! The `no-rsa-pkcs1-encrypt` Cargo feature makes [`PkeyCtxRef::set_rsa_padding`] reject PKCS#1 v1.5 padding on
+//! contexts initialized for RSA encryption or decryption, since it is vulnerable to padding oracle attacks. Nothing else
+//! is affected: a context on which no padding is set still uses OpenSSL's default of PKCS#1 v1.5, and APIs such as
+//! [`RsaRef::public_encrypt`] are unchanged. The feature requires OpenSSL 1.1.0 or newer and does nothing on older
+//! versions. Since Cargo features are unified, enabling it in any crate of a build applies it to the whole build.
+//!
+//! [`PkeyCtxRef::set_rsa_padding`]: pkey_ctx::PkeyCtxRef::set_rsa_padding
+//! [`RsaRef::public_encrypt`]: rsa::RsaRef::public_encrypt
+//!
 //! # Feature Detection
 //!
 //! APIs have been added to and removed from the various supported OpenSSL versions, and this library exposes the
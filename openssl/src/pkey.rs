@@ -40,6 +40,7 @@
 //! println!("{:?}", str::from_utf8(pub_key.as_slice()).unwrap());
 //! ```
 #![allow(clippy::missing_safety_doc)]
+use crate::base64;
 use crate::bio::{MemBio, MemBioSlice};
 #[cfg(ossl110)]
 use crate::cipher::CipherRef;
@@ -94,6 +95,10 @@ impl Id {
 
     #[cfg(any(ossl110, boringssl, libressl360, awslc))]
     pub const HKDF: Id = Id(ffi::EVP_PKEY_HKDF);
+    #[cfg(ossl110)]
+    pub const SCRYPT: Id = Id(ffi::EVP_PKEY_SCRYPT);
+    #[cfg(ossl110)]
+    pub const TLS1_PRF: Id = Id(ffi::EVP_PKEY_TLS1_PRF);
 
     #[cfg(any(ossl111, boringssl, libressl370, awslc))]
     pub const ED25519: Id = Id(ffi::EVP_PKEY_ED25519);
@@ -283,6 +288,30 @@ where
             Ok(buf)
         }
     }
+
+    /// Serializes an Ed25519 public key as an OpenSSH `authorized_keys` line.
+    ///
+    /// The output has the form `ssh-ed25519 AAAA...`, where the base64 blob holds the SSH wire
+    /// encoding of the key. No comment is appended. An error is returned for any other key type.
+    #[cfg(any(ossl111, boringssl, libressl370, awslc))]
+    pub fn public_key_to_openssh(&self) -> Result<String, ErrorStack> {
+        const KEY_TYPE: &str = "ssh-ed25519";
+
+        if self.id() != Id::ED25519 {
+            return Err(ErrorStack::raise(
+                ffi::ERR_LIB_EVP,
+                ffi::EVP_R_OPERATION_NOT_SUPPORTED_FOR_THIS_KEYTYPE,
+            ));
+        }
+        let raw = self.raw_public_key()?;
+
+        let mut blob = Vec::with_capacity(8 + KEY_TYPE.len() + raw.len());
+        for field in [KEY_TYPE.as_bytes(), &raw] {
+            blob.extend_from_slice(&(field.len() as u32).to_be_bytes());
+            blob.extend_from_slice(field);
+        }
+        Ok(format!("{} {}", KEY_TYPE, base64::encode_block(&blob)))
+    }
 }
 
 impl<T> PKeyRef<T>
@@ -398,6 +427,10 @@ impl<T> fmt::Debug for PKey<T> {
             Id::SM2 => "SM2",
             #[cfg(any(ossl110, boringssl, libressl360, awslc))]
             Id::HKDF => "HKDF",
+            #[cfg(ossl110)]
+            Id::SCRYPT => "scrypt",
+            #[cfg(ossl110)]
+            Id::TLS1_PRF => "TLS1-PRF",
             #[cfg(any(ossl111, boringssl, libressl370, awslc))]
             Id::ED25519 => "Ed25519",
             #[cfg(ossl111)]
@@ -905,7 +938,7 @@ mod tests {
     #[cfg(not(boringssl))]
     use crate::dh::Dh;
     use crate::dsa::Dsa;
-    use crate::ec::EcKey;
+    use crate::ec::{EcGroup, EcKey};
     use crate::error::Error;
     use crate::nid::Nid;
     use crate::rsa::Rsa;
@@ -1122,6 +1155,33 @@ mod tests {
         );
     }
 
+    #[test]
+    #[cfg(any(ossl111, boringssl, libressl370, awslc))]
+    fn test_ed25519_openssh_public_key() {
+        let mut ctx = PkeyCtx::new_id(Id::ED25519).unwrap();
+        ctx.keygen_init().unwrap();
+        let key = ctx.keygen().unwrap();
+
+        let line = key.public_key_to_openssh().unwrap();
+        let (key_type, encoded) = line.split_once(' ').unwrap();
+        assert_eq!(key_type, "ssh-ed25519");
+        assert!(encoded.starts_with("AAAA"));
+
+        let blob = base64::decode_block(encoded).unwrap();
+        assert_eq!(blob.len(), 51);
+        assert_eq!(&blob[..4], &[0, 0, 0, 11]);
+        assert_eq!(&blob[4..15], b"ssh-ed25519");
+        assert_eq!(&blob[15..19], &[0, 0, 0, 32]);
+        let parsed = PKey::public_key_from_raw_bytes(&blob[19..], Id::ED25519).unwrap();
+        assert!(key.public_eq(&parsed));
+
+        let ec = PKey::from_ec_key(
+            EcKey::generate(&EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).unwrap()).unwrap(),
+        )
+        .unwrap();
+        assert!(ec.public_key_to_openssh().is_err());
+    }
+
     #[cfg(any(ossl111, boringssl, libressl370, awslc))]
     fn test_raw_private_key(gen: fn() -> Result<PKey<Private>, ErrorStack>, key_type: Id) {
         // Generate a new key
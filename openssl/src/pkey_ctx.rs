@@ -64,27 +64,52 @@ let cmac_key = ctx.keygen().unwrap();
 //! let valid = ctx.verify(text, &signature).unwrap();
 //! assert!(valid);
 //! ```
-use crate::bn::BigNumRef;
-#[cfg(not(any(boringssl, awslc)))]
+#[cfg(ossl300)]
+use crate::asn1::Asn1ObjectRef;
+#[cfg(any(ossl110, boringssl, libressl360, awslc))]
+use crate::bn::BigNumContext;
+use crate::bn::{BigNum, BigNumRef};
 use crate::cipher::CipherRef;
+#[cfg(any(ossl110, boringssl, libressl360, awslc))]
+use crate::ec::{Asn1Flag, EcKey, EcPoint, PointConversionForm};
+use crate::ecdsa::EcdsaSig;
 use crate::error::ErrorStack;
-use crate::md::MdRef;
+use crate::hash::{hash, MessageDigest};
+use crate::md::{Md, MdRef};
+use crate::memcmp;
 use crate::nid::Nid;
-use crate::pkey::{HasPrivate, HasPublic, Id, PKey, PKeyRef, Params, Private};
+use crate::pkey::{HasPrivate, HasPublic, Id, PKey, PKeyRef, Params, Private, Public};
+#[cfg(any(ossl110, boringssl, libressl360, awslc))]
+use crate::rand::rand_bytes;
 use crate::rsa::Padding;
 use crate::sign::RsaPssSaltlen;
-use crate::{cvt, cvt_p};
+#[cfg(any(ossl110, boringssl, libressl360, awslc))]
+use crate::sign::Signer;
+#[cfg(any(ossl110, boringssl, libressl360, awslc))]
+use crate::symm;
+use crate::util::ForeignTypeRefExt;
+use crate::{cvt, cvt_n, cvt_p};
 use cfg_if::cfg_if;
 use foreign_types::{ForeignType, ForeignTypeRef};
-#[cfg(not(any(boringssl, awslc)))]
 use libc::c_int;
 #[cfg(ossl320)]
 use libc::c_uint;
+#[cfg(not(any(boringssl, awslc)))]
+use libc::c_void;
 use openssl_macros::corresponds;
+#[cfg(all(debug_assertions, any(ossl110, boringssl, libressl360, awslc)))]
+use std::cell::Cell;
 use std::convert::TryFrom;
-#[cfg(ossl320)]
+#[cfg(not(any(boringssl, awslc)))]
+use std::error::Error;
+#[cfg(ossl300)]
 use std::ffi::CStr;
+#[cfg(not(any(boringssl, awslc)))]
+use std::fmt;
 use std::ptr;
+#[cfg(not(any(boringssl, awslc)))]
+use std::time::{Duration, Instant};
+use std::{panic, thread};
 
 /// HKDF modes of operation.
 #[cfg(any(ossl111, libressl360))]
@@ -112,6 +137,30 @@ impl HkdfMode {
     pub const EXPAND_ONLY: Self = HkdfMode(ffi::EVP_PKEY_HKDEF_MODE_EXPAND_ONLY);
 }
 
+/// EdDSA instances, as defined in RFC 8032.
+#[cfg(ossl320)]
+#[derive(Debug, PartialEq)]
+pub struct EddsaInstance(&'static [u8]);
+
+#[cfg(ossl320)]
+impl EddsaInstance {
+    /// Pure Ed25519, the default for Ed25519 keys. Corresponds to the instance name `Ed25519`.
+    pub const ED25519: Self = EddsaInstance(b"Ed25519\0");
+
+    /// Ed25519 with a context string. Corresponds to the instance name `Ed25519ctx`.
+    pub const ED25519CTX: Self = EddsaInstance(b"Ed25519ctx\0");
+
+    /// Ed25519 over the SHA-512 hash of the message. Corresponds to the instance name
+    /// `Ed25519ph`.
+    pub const ED25519PH: Self = EddsaInstance(b"Ed25519ph\0");
+
+    /// Pure Ed448, the default for Ed448 keys. Corresponds to the instance name `Ed448`.
+    pub const ED448: Self = EddsaInstance(b"Ed448\0");
+
+    /// Ed448 over the SHAKE256 hash of the message. Corresponds to the instance name `Ed448ph`.
+    pub const ED448PH: Self = EddsaInstance(b"Ed448ph\0");
+}
+
 /// Nonce type for ECDSA and DSA.
 #[cfg(ossl320)]
 #[derive(Debug, PartialEq)]
@@ -127,6 +176,94 @@ impl NonceType {
     pub const DETERMINISTIC_K: Self = NonceType(1);
 }
 
+/// Key derivation functions applied to the shared secret of a DH key exchange.
+#[cfg(ossl300)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct DhKdfType(c_int);
+
+#[cfg(ossl300)]
+impl DhKdfType {
+    /// This is the default. The raw shared secret is returned by [`derive`][PkeyCtxRef::derive].
+    pub const NONE: Self = DhKdfType(ffi::EVP_PKEY_DH_KDF_NONE);
+
+    /// The shared secret is passed through the ANSI X9.42 KDF as used by CMS (RFC 2631).
+    ///
+    /// The digest, output length and key encryption algorithm OID must be set before a key is
+    /// derived or an error occurs.
+    pub const X9_42: Self = DhKdfType(ffi::EVP_PKEY_DH_KDF_X9_42);
+}
+
+/// A signature algorithm, as configured by [`PkeyCtxRef::configure_signature`].
+///
+/// The schemes correspond to the JWS algorithms of the same names in RFC 7518 and to the TLS 1.3
+/// signature schemes in RFC 8446.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SignatureScheme {
+    /// RSASSA-PKCS1-v1_5 with SHA-256 (`RS256`, `rsa_pkcs1_sha256`).
+    RsaPkcs1Sha256,
+    /// RSASSA-PSS with SHA-256, MGF1 with SHA-256 and a 32 byte salt (`PS256`,
+    /// `rsa_pss_rsae_sha256`).
+    RsaPssSha256,
+    /// ECDSA with SHA-256 (`ES256`, `ecdsa_secp256r1_sha256`).
+    ///
+    /// The curve of the key is not checked.
+    EcdsaSha256,
+    /// Pure Ed25519 (`EdDSA`, `ed25519`).
+    ///
+    /// Ed25519 signs the message itself rather than a digest, so it must be used through
+    /// [`MdCtx`](crate::md_ctx::MdCtx) with no digest.
+    #[cfg(any(ossl111, boringssl, libressl370, awslc))]
+    Ed25519,
+}
+
+/// A complete RSA-OAEP configuration, applied by [`PkeyCtxRef::apply_rsa_oaep`].
+#[derive(Copy, Clone)]
+pub struct RsaOaepConfig<'a> {
+    /// The digest used to hash the label.
+    pub oaep_md: &'a MdRef,
+    /// The digest used by the MGF1 mask generation function.
+    pub mgf1_md: &'a MdRef,
+    /// The label, if any.
+    pub label: Option<&'a [u8]>,
+}
+
+/// An error returned by [`PkeyCtxRef::keygen_with_timeout`].
+#[cfg(not(any(boringssl, awslc)))]
+#[derive(Debug)]
+pub enum KeygenError {
+    /// Key generation did not finish before the deadline and was aborted.
+    Timeout,
+    /// OpenSSL failed to generate the key.
+    ErrorStack(ErrorStack),
+}
+
+#[cfg(not(any(boringssl, awslc)))]
+impl fmt::Display for KeygenError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            KeygenError::Timeout => fmt.write_str("key generation timed out"),
+            KeygenError::ErrorStack(ref e) => fmt::Display::fmt(e, fmt),
+        }
+    }
+}
+
+#[cfg(not(any(boringssl, awslc)))]
+impl Error for KeygenError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            KeygenError::Timeout => None,
+            KeygenError::ErrorStack(ref e) => Some(e),
+        }
+    }
+}
+
+#[cfg(not(any(boringssl, awslc)))]
+impl From<ErrorStack> for KeygenError {
+    fn from(e: ErrorStack) -> KeygenError {
+        KeygenError::ErrorStack(e)
+    }
+}
+
 generic_foreign_type_and_impl_send_sync! {
     type CType = ffi::EVP_PKEY_CTX;
     fn drop = ffi::EVP_PKEY_CTX_free;
@@ -147,6 +284,35 @@ impl<T> PkeyCtx<T> {
             Ok(PkeyCtx::from_ptr(ptr))
         }
     }
+
+    /// Restricts this context to algorithm implementations from the FIPS provider.
+    ///
+    /// The context is recreated for the same key with the `fips=yes` property query, so this
+    /// must be called before any operation is initialized; configuration applied earlier is
+    /// discarded. An error is returned if the FIPS provider is not loaded or cannot handle the
+    /// key, and operations which the FIPS provider does not implement fail at their `*_init`
+    /// call. Contexts created without a key, such as by [`PkeyCtx::new_id`], are not supported.
+    ///
+    /// Requires OpenSSL 3.0.0 or newer.
+    #[corresponds(EVP_PKEY_CTX_new_from_pkey)]
+    #[cfg(ossl300)]
+    pub fn require_fips(&mut self) -> Result<(), ErrorStack> {
+        let pkey = unsafe { ffi::EVP_PKEY_CTX_get0_pkey(self.as_ptr()) };
+        if pkey.is_null() {
+            return Err(ErrorStack::raise(ffi::ERR_LIB_EVP, ffi::EVP_R_NO_KEY_SET));
+        }
+
+        unsafe {
+            let ptr = cvt_p(ffi::EVP_PKEY_CTX_new_from_pkey(
+                ptr::null_mut(),
+                pkey,
+                b"fips=yes\0".as_ptr() as *const _,
+            ))?;
+            *self = PkeyCtx::from_ptr(ptr);
+        }
+
+        Ok(())
+    }
 }
 
 impl PkeyCtx<()> {
@@ -231,8 +397,8 @@ where
 
     /// Verifies the signature of data using the public key.
     ///
-    /// Returns `Ok(true)` if the signature is valid, `Ok(false)` if the signature is invalid, and `Err` if an error
-    /// occurred.
+    /// Returns `Ok(true)` if the signature is valid, `Ok(false)` if the signature is invalid, and `Err` if the
+    /// verification could not be performed.
     ///
     /// # Note
     ///
@@ -250,19 +416,16 @@ where
                 data.as_ptr(),
                 data.len(),
             );
-            // `EVP_PKEY_verify` is not terribly consistent about how it,
-            // reports errors. It does not clearly distinguish between 0 and
-            // -1, and may put errors on the stack in both cases. If there's
-            // errors on the stack, we return `Err()`, else we return
-            // `Ok(false)`.
-            if r <= 0 {
-                let errors = ErrorStack::get();
-                if !errors.errors().is_empty() {
-                    return Err(errors);
-                }
+            // `EVP_PKEY_verify` returns 0 for a signature which does not
+            // verify, but may still put errors on the stack describing why,
+            // so those are discarded. Negative values indicate a failure to
+            // perform the verification at all.
+            let errors = ErrorStack::get();
+            match r {
+                1 => Ok(true),
+                0 => Ok(false),
+                _ => Err(errors),
             }
-
-            Ok(r == 1)
         }
     }
 
@@ -291,6 +454,54 @@ where
 
         Ok(written)
     }
+
+    /// Verifies an ECDSA signature in the fixed-width `R || S` format used by JWS.
+    ///
+    /// This is the counterpart of [`sign_ecdsa_jose`][PkeyCtxRef::sign_ecdsa_jose]. A signature
+    /// whose length is not twice the byte length of the curve order is reported as invalid. The
+    /// context must be initialized for verification with an EC key.
+    pub fn verify_ecdsa_jose(&mut self, tbs: &[u8], sig: &[u8]) -> Result<bool, ErrorStack> {
+        let len = self.ecdsa_jose_len()? as usize;
+        if sig.len() != 2 * len {
+            return Ok(false);
+        }
+
+        let r = BigNum::from_slice(&sig[..len])?;
+        let s = BigNum::from_slice(&sig[len..])?;
+        let der = EcdsaSig::from_private_components(r, s)?.to_der()?;
+        self.verify(tbs, &der)
+    }
+
+    /// Verifies a fixed-width `R || S` ECDSA signature, as used by COSE and WebAuthn.
+    ///
+    /// This is the counterpart of [`sign_ecdsa_fixed`][PkeyCtxRef::sign_ecdsa_fixed]. An error is
+    /// returned if `coord_len` is not the byte length of the curve order, and a signature whose
+    /// length is not `2 * coord_len` is reported as invalid.
+    pub fn verify_ecdsa_fixed(
+        &mut self,
+        tbs: &[u8],
+        sig: &[u8],
+        coord_len: usize,
+    ) -> Result<bool, ErrorStack> {
+        self.check_ecdsa_coord_len(coord_len)?;
+        self.verify_ecdsa_jose(tbs, sig)
+    }
+
+    /// Applies the raw RSA public key operation to `block`.
+    ///
+    /// `block` must already be padded by the caller and be exactly as long as the RSA modulus.
+    /// The context is reinitialized for encryption with [`Padding::NONE`], discarding any previous
+    /// configuration. This is intended for testing non-standard padding schemes; use
+    /// [`Padding::PKCS1_OAEP`] for encryption.
+    pub fn raw_public_encrypt(&mut self, block: &[u8]) -> Result<Vec<u8>, ErrorStack> {
+        self.check_rsa_block_len(block)?;
+        self.encrypt_init()?;
+        self.set_rsa_padding(Padding::NONE)?;
+
+        let mut out = vec![];
+        self.encrypt_to_vec(block, &mut out)?;
+        Ok(out)
+    }
 }
 
 impl<T> PkeyCtxRef<T>
@@ -363,6 +574,21 @@ where
         Ok(len)
     }
 
+    /// Like [`Self::decrypt`] but replaces the contents of `out` with the plaintext.
+    ///
+    /// The plaintext is written into the existing allocation of `out`, so reusing the same [`Vec`]
+    /// across calls avoids an allocation per call once its capacity covers the output size. On
+    /// error `out` is left empty.
+    #[corresponds(EVP_PKEY_decrypt)]
+    pub fn decrypt_into(&mut self, from: &[u8], out: &mut Vec<u8>) -> Result<usize, ErrorStack> {
+        out.clear();
+        let r = self.decrypt_to_vec(from, out);
+        if r.is_err() {
+            out.clear();
+        }
+        r
+    }
+
     /// Signs the contents of `data`.
     ///
     /// If `sig` is set to `None`, an upper bound on the number of bytes required for the output buffer will be
@@ -398,9 +624,88 @@ where
         sig.truncate(base + len);
         Ok(len)
     }
+
+    /// Computes an RSA PKCS#1 v1.5 signature over an already-encoded `DigestInfo`.
+    ///
+    /// `digestinfo` must be the complete DER encoding of the `DigestInfo` structure from RFC 8017
+    /// section 9.2, i.e. the digest algorithm identifier followed by the digest. Only the
+    /// EMSA-PKCS1-v1_5 block padding is added, so the result is identical to a signature made with
+    /// [`set_signature_md`][PkeyCtxRef::set_signature_md] over the bare digest.
+    ///
+    /// The context is reinitialized for signing with [`Padding::PKCS1`] and no signature digest,
+    /// discarding any previous configuration. This is only useful for RSA keys.
+    pub fn sign_digestinfo(&mut self, digestinfo: &[u8]) -> Result<Vec<u8>, ErrorStack> {
+        self.sign_init()?;
+        self.set_rsa_padding(Padding::PKCS1)?;
+
+        let mut sig = vec![];
+        self.sign_to_vec(digestinfo, &mut sig)?;
+        Ok(sig)
+    }
+
+    /// Computes an ECDSA signature in the fixed-width `R || S` format used by JWS.
+    ///
+    /// `tbs` is the digest to sign, such as the SHA-256 hash of the JWS signing input for `ES256`.
+    /// `R` and `S` are each left-padded with zeros to the byte length of the curve order, as
+    /// described in RFC 7518 section 3.4. The context must be initialized for signing with an EC
+    /// key.
+    pub fn sign_ecdsa_jose(&mut self, tbs: &[u8]) -> Result<Vec<u8>, ErrorStack> {
+        let len = self.ecdsa_jose_len()?;
+
+        let mut der = vec![];
+        self.sign_to_vec(tbs, &mut der)?;
+        let sig = EcdsaSig::from_der(&der)?;
+
+        let mut out = sig.r().to_vec_padded(len)?;
+        out.extend(sig.s().to_vec_padded(len)?);
+        Ok(out)
+    }
+
+    /// Computes a fixed-width `R || S` ECDSA signature of exactly `2 * coord_len` bytes.
+    ///
+    /// This is the format used by COSE (RFC 8152 section 8.1), and matches
+    /// [`sign_ecdsa_jose`][PkeyCtxRef::sign_ecdsa_jose] except that the expected width is given
+    /// explicitly. An error is returned if `coord_len` is not the byte length of the curve order,
+    /// such as 32 for P-256.
+    pub fn sign_ecdsa_fixed(
+        &mut self,
+        tbs: &[u8],
+        coord_len: usize,
+    ) -> Result<Vec<u8>, ErrorStack> {
+        self.check_ecdsa_coord_len(coord_len)?;
+        self.sign_ecdsa_jose(tbs)
+    }
+
+    /// Applies the raw RSA private key operation to `block`, without removing any padding.
+    ///
+    /// This is the counterpart of [`raw_public_encrypt`][PkeyCtxRef::raw_public_encrypt], and
+    /// `block` must be exactly as long as the RSA modulus. The context is reinitialized for
+    /// decryption with [`Padding::NONE`], discarding any previous configuration.
+    pub fn raw_private_decrypt(&mut self, block: &[u8]) -> Result<Vec<u8>, ErrorStack> {
+        self.check_rsa_block_len(block)?;
+        self.decrypt_init()?;
+        self.set_rsa_padding(Padding::NONE)?;
+
+        let mut out = vec![];
+        self.decrypt_to_vec(block, &mut out)?;
+        Ok(out)
+    }
 }
 
 impl<T> PkeyCtxRef<T> {
+    /// Returns a copy of the context.
+    ///
+    /// The copy has the same key, initialized operation and settings, such as the padding mode
+    /// and digests, but is otherwise independent of the original. This allows a context to be
+    /// configured once and then used by several workers.
+    #[corresponds(EVP_PKEY_CTX_dup)]
+    pub fn try_clone(&self) -> Result<PkeyCtx<T>, ErrorStack> {
+        unsafe {
+            let ptr = cvt_p(ffi::EVP_PKEY_CTX_dup(self.as_ptr()))?;
+            Ok(PkeyCtx::from_ptr(ptr))
+        }
+    }
+
     /// Prepares the context for shared secret derivation.
     #[corresponds(EVP_PKEY_derive_init)]
     #[inline]
@@ -424,6 +729,9 @@ impl<T> PkeyCtxRef<T> {
     }
 
     /// Prepares the context for key parameter generation.
+    ///
+    /// This must be called before the algorithm's paramgen controls, such as
+    /// [`set_dsa_paramgen_bits`](Self::set_dsa_paramgen_bits), and [`paramgen`](Self::paramgen).
     #[corresponds(EVP_PKEY_paramgen_init)]
     #[inline]
     pub fn paramgen_init(&mut self) -> Result<(), ErrorStack> {
@@ -450,6 +758,30 @@ impl<T> PkeyCtxRef<T> {
         Ok(())
     }
 
+    /// Returns the signature digest set with [`set_signature_md`](Self::set_signature_md).
+    ///
+    /// An error is returned if no signature digest has been set.
+    #[corresponds(EVP_PKEY_CTX_get_signature_md)]
+    #[inline]
+    pub fn signature_md(&self) -> Result<&MdRef, ErrorStack> {
+        let mut md = ptr::null();
+        unsafe {
+            cvt(ffi::EVP_PKEY_CTX_get_signature_md(self.as_ptr(), &mut md))?;
+            MdRef::from_const_ptr_opt(md)
+        }
+        .ok_or_else(|| ErrorStack::raise(ffi::ERR_LIB_EVP, ffi::EVP_R_UNKNOWN_DIGEST))
+    }
+
+    /// Like [`set_signature_md`](Self::set_signature_md), but looks up the digest by its NID.
+    ///
+    /// This is convenient when the digest comes from a parsed signature algorithm. An error is
+    /// returned if `nid` does not identify a digest.
+    pub fn set_signature_md_nid(&self, nid: Nid) -> Result<(), ErrorStack> {
+        let md = Md::from_nid(nid)
+            .ok_or_else(|| ErrorStack::raise(ffi::ERR_LIB_EVP, ffi::EVP_R_UNKNOWN_DIGEST))?;
+        self.set_signature_md(md)
+    }
+
     /// Sets the DH paramgen prime length.
     ///
     /// This is only useful for DH keys.
@@ -484,6 +816,49 @@ impl<T> PkeyCtxRef<T> {
         Ok(())
     }
 
+    /// Selects one of the predefined DH groups from RFC 5114 for parameter generation.
+    ///
+    /// `group` is 1 for the 1024-bit group with a 160-bit subgroup, 2 for the 2048-bit group with
+    /// a 224-bit subgroup, or 3 for the 2048-bit group with a 256-bit subgroup. These groups are
+    /// only provided for compatibility with existing systems: their generation process is
+    /// unexplained, and they have been deprecated in favor of the RFC 7919 groups.
+    ///
+    /// This is only useful for DHX keys, after calling [`paramgen_init`](Self::paramgen_init).
+    #[corresponds(EVP_PKEY_CTX_set_dh_rfc5114)]
+    #[cfg(not(any(libressl, boringssl, awslc)))]
+    #[inline]
+    pub fn set_dh_rfc5114(&mut self, group: u8) -> Result<(), ErrorStack> {
+        unsafe {
+            cvt(ffi::EVP_PKEY_CTX_set_dh_rfc5114(
+                self.as_ptr(),
+                group as c_int,
+            ))?;
+        }
+
+        Ok(())
+    }
+
+    /// Sets whether a derived DH shared secret is padded with leading zeros to the size of the
+    /// prime.
+    ///
+    /// Without padding, a secret with leading zero bytes is returned shorter than the prime, as
+    /// PKCS #3 specifies. Peers which expect fixed-length output, such as TLS 1.3 (RFC 8446) and
+    /// NIST SP 800-56A, then derive different keys.
+    ///
+    /// This is only useful for DH keys, after calling [`derive_init`][Self::derive_init].
+    ///
+    /// Requires OpenSSL 1.1.1 or newer.
+    #[corresponds(EVP_PKEY_CTX_set_dh_pad)]
+    #[cfg(ossl111)]
+    #[inline]
+    pub fn set_dh_pad(&mut self, pad: bool) -> Result<(), ErrorStack> {
+        unsafe {
+            cvt(ffi::EVP_PKEY_CTX_set_dh_pad(self.as_ptr(), pad as c_int))?;
+        }
+
+        Ok(())
+    }
+
     /// Sets the DSA paramgen bits.
     ///
     /// This is only useful for DSA keys.
@@ -502,6 +877,10 @@ impl<T> PkeyCtxRef<T> {
 
     /// Sets the EC paramgen curve NID.
     ///
+    /// This selects the named curve of keys generated by [`keygen`](Self::keygen) or parameters
+    /// generated by [`paramgen`](Self::paramgen), so it must be called after
+    /// [`keygen_init`](Self::keygen_init) or [`paramgen_init`](Self::paramgen_init).
+    ///
     /// This is only useful for EC keys.
     #[corresponds(EVP_PKEY_CTX_set_ec_paramgen_curve_nid)]
     #[inline]
@@ -516,6 +895,114 @@ impl<T> PkeyCtxRef<T> {
         Ok(())
     }
 
+    /// Sets whether generated EC parameters are encoded as a named curve or explicitly.
+    ///
+    /// [`Asn1Flag::NAMED_CURVE`] is the default and should be preferred, since many
+    /// implementations reject explicit curve parameters. Like
+    /// [`set_ec_paramgen_curve_nid`](Self::set_ec_paramgen_curve_nid), this must be called after
+    /// [`keygen_init`](Self::keygen_init) or [`paramgen_init`](Self::paramgen_init).
+    ///
+    /// This is only useful for EC keys.
+    #[corresponds(EVP_PKEY_CTX_set_ec_param_enc)]
+    #[inline]
+    pub fn set_ec_param_enc(&mut self, enc: Asn1Flag) -> Result<(), ErrorStack> {
+        unsafe {
+            cvt(ffi::EVP_PKEY_CTX_set_ec_param_enc(self.as_ptr(), enc.0))?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the key associated with the context, if any.
+    fn pkey(&self) -> Option<&PKeyRef<T>> {
+        unsafe {
+            let pkey = ffi::EVP_PKEY_CTX_get0_pkey(self.as_ptr());
+            if pkey.is_null() {
+                None
+            } else {
+                Some(PKeyRef::from_ptr(pkey))
+            }
+        }
+    }
+
+    /// Returns the width of each of `R` and `S` in a JWS ECDSA signature.
+    fn ecdsa_jose_len(&self) -> Result<i32, ErrorStack> {
+        let pkey = self
+            .pkey()
+            .ok_or_else(|| ErrorStack::raise(ffi::ERR_LIB_EVP, ffi::EVP_R_NO_KEY_SET))?;
+        if pkey.id() != Id::EC {
+            return Err(ErrorStack::raise(
+                ffi::ERR_LIB_EVP,
+                ffi::EVP_R_EXPECTING_A_EC_KEY,
+            ));
+        }
+
+        let bits = unsafe { ffi::EVP_PKEY_bits(pkey.as_ptr()) };
+        Ok((bits + 7) / 8)
+    }
+
+    fn check_ecdsa_coord_len(&self, coord_len: usize) -> Result<(), ErrorStack> {
+        if coord_len != self.ecdsa_jose_len()? as usize {
+            return Err(ErrorStack::raise(ffi::ERR_LIB_EC, ffi::EC_R_INVALID_LENGTH));
+        }
+        Ok(())
+    }
+
+    /// Returns the size of the RSA modulus in bytes.
+    ///
+    /// Returns `None` if the context's key is not an RSA key.
+    #[corresponds(EVP_PKEY_CTX_get0_pkey)]
+    pub fn rsa_size(&self) -> Option<usize> {
+        let pkey = self.pkey()?;
+        match pkey.id() {
+            Id::RSA => Some(pkey.size()),
+            #[cfg(any(ossl111, libressl, boringssl, awslc))]
+            Id::RSA_PSS => Some(pkey.size()),
+            _ => None,
+        }
+    }
+
+    /// Checks that `block` is exactly as long as the RSA modulus.
+    fn check_rsa_block_len(&self, block: &[u8]) -> Result<(), ErrorStack> {
+        let size = self
+            .rsa_size()
+            .ok_or_else(|| ErrorStack::raise(ffi::ERR_LIB_EVP, ffi::EVP_R_EXPECTING_AN_RSA_KEY))?;
+        if block.len() < size {
+            return Err(ErrorStack::raise(
+                ffi::ERR_LIB_RSA,
+                ffi::RSA_R_DATA_TOO_SMALL_FOR_KEY_SIZE,
+            ));
+        }
+        if block.len() > size {
+            return Err(ErrorStack::raise(
+                ffi::ERR_LIB_RSA,
+                ffi::RSA_R_DATA_TOO_LARGE_FOR_KEY_SIZE,
+            ));
+        }
+        Ok(())
+    }
+
+    /// Returns the maximum length of a message which can be encrypted with RSA-OAEP.
+    ///
+    /// The bound is `k - 2 * hLen - 2` from RFC 8017 section 7.1.1, where `k` is the size of the
+    /// RSA modulus and `hLen` is the output size of the configured OAEP digest. An error is
+    /// returned unless the context has an RSA key and uses [`Padding::PKCS1_OAEP`].
+    pub fn rsa_oaep_max_message_len(&self) -> Result<usize, ErrorStack> {
+        if self.rsa_padding()? != Padding::PKCS1_OAEP {
+            return Err(ErrorStack::raise(
+                ffi::ERR_LIB_RSA,
+                ffi::RSA_R_ILLEGAL_OR_UNSUPPORTED_PADDING_MODE,
+            ));
+        }
+        let k = self
+            .rsa_size()
+            .ok_or_else(|| ErrorStack::raise(ffi::ERR_LIB_EVP, ffi::EVP_R_EXPECTING_AN_RSA_KEY))?;
+        let md = self.rsa_oaep_md()?;
+
+        k.checked_sub(2 * md.size() + 2)
+            .ok_or_else(|| ErrorStack::raise(ffi::ERR_LIB_RSA, ffi::RSA_R_KEY_SIZE_TOO_SMALL))
+    }
+
     /// Returns the RSA padding mode in use.
     ///
     /// This is only useful for RSA keys.
@@ -533,9 +1020,24 @@ impl<T> PkeyCtxRef<T> {
     /// Sets the RSA padding mode.
     ///
     /// This is only useful for RSA keys.
+    ///
+    /// If the `no-rsa-pkcs1-encrypt` Cargo feature is enabled, [`Padding::PKCS1`] is rejected with
+    /// an `RSA_R_ILLEGAL_OR_UNSUPPORTED_PADDING_MODE` error on contexts initialized for encryption
+    /// or decryption, since PKCS#1 v1.5 encryption is prone to padding oracle attacks. Use
+    /// [`Padding::PKCS1_OAEP`] instead. Signatures are not affected, and neither is a context on
+    /// which no padding is set, which still uses PKCS#1 v1.5 for encryption. The feature requires
+    /// OpenSSL 1.1.0 or newer and has no effect on older versions.
     #[corresponds(EVP_PKEY_CTX_set_rsa_padding)]
     #[inline]
     pub fn set_rsa_padding(&mut self, padding: Padding) -> Result<(), ErrorStack> {
+        #[cfg(all(feature = "no-rsa-pkcs1-encrypt", ossl110))]
+        if padding == Padding::PKCS1 && self.operation_flags() & ffi::EVP_PKEY_OP_TYPE_CRYPT != 0 {
+            return Err(ErrorStack::raise(
+                ffi::ERR_LIB_RSA,
+                ffi::RSA_R_ILLEGAL_OR_UNSUPPORTED_PADDING_MODE,
+            ));
+        }
+
         unsafe {
             cvt(ffi::EVP_PKEY_CTX_set_rsa_padding(
                 self.as_ptr(),
@@ -546,12 +1048,58 @@ impl<T> PkeyCtxRef<T> {
         Ok(())
     }
 
-    /// Sets the RSA keygen bits.
+    /// Sets the RSA padding mode from its name.
+    ///
+    /// `name` is one of `"pkcs1"`, `"oaep"`, `"pss"` or `"none"`, matching the values accepted by
+    /// the `rsa_padding_mode` option of the `openssl pkeyutl` command. An unknown name is reported
+    /// as an `RSA_R_UNKNOWN_PADDING_TYPE` error without modifying the context.
     ///
     /// This is only useful for RSA keys.
+    pub fn set_rsa_padding_mode(&mut self, name: &str) -> Result<(), ErrorStack> {
+        let padding = match name {
+            "pkcs1" => Padding::PKCS1,
+            "oaep" => Padding::PKCS1_OAEP,
+            "pss" => Padding::PKCS1_PSS,
+            "none" => Padding::NONE,
+            _ => {
+                return Err(ErrorStack::raise(
+                    ffi::ERR_LIB_RSA,
+                    ffi::RSA_R_UNKNOWN_PADDING_TYPE,
+                ))
+            }
+        };
+        self.set_rsa_padding(padding)
+    }
+
+    /// Sets the RSA keygen bits.
+    ///
+    /// Sizes below 512 bits are rejected, since such keys can be factored trivially. Use
+    /// [`set_rsa_keygen_bits_unchecked`][Self::set_rsa_keygen_bits_unchecked] to pass the size to
+    /// the library without this check.
+    ///
+    /// This is only useful for RSA keys, after [`keygen_init`](Self::keygen_init).
     #[corresponds(EVP_PKEY_CTX_set_rsa_keygen_bits)]
     #[inline]
     pub fn set_rsa_keygen_bits(&mut self, bits: u32) -> Result<(), ErrorStack> {
+        if bits < 512 {
+            return Err(ErrorStack::raise(
+                ffi::ERR_LIB_RSA,
+                ffi::RSA_R_KEY_SIZE_TOO_SMALL,
+            ));
+        }
+
+        self.set_rsa_keygen_bits_unchecked(bits)
+    }
+
+    /// Sets the RSA keygen bits without enforcing the 512 bit floor of
+    /// [`set_rsa_keygen_bits`][Self::set_rsa_keygen_bits].
+    ///
+    /// The size is passed to the library as is, which applies its own limits.
+    ///
+    /// This is only useful for RSA keys.
+    #[corresponds(EVP_PKEY_CTX_set_rsa_keygen_bits)]
+    #[inline]
+    pub fn set_rsa_keygen_bits_unchecked(&mut self, bits: u32) -> Result<(), ErrorStack> {
         unsafe {
             cvt(ffi::EVP_PKEY_CTX_set_rsa_keygen_bits(
                 self.as_ptr(),
@@ -564,10 +1112,21 @@ impl<T> PkeyCtxRef<T> {
 
     /// Sets the RSA keygen public exponent.
     ///
-    /// This is only useful for RSA keys.
+    /// The exponent must be odd and at least 3, otherwise an error is returned. Exponents below
+    /// 65537 are accepted but not recommended, since small exponents make implementation flaws
+    /// such as missing padding much easier to exploit.
+    ///
+    /// The context stores its own copy of the exponent, so `pubexp` remains owned by the caller
+    /// and may be modified or dropped before [`keygen`](Self::keygen) is called.
+    ///
+    /// This is only useful for RSA keys, after [`keygen_init`](Self::keygen_init).
     #[corresponds(EVP_PKEY_CTX_set1_rsa_keygen_pubexp)]
     #[inline]
     pub fn set_rsa_keygen_pubexp(&mut self, pubexp: &BigNumRef) -> Result<(), ErrorStack> {
+        if pubexp.is_negative() || !pubexp.is_odd() || pubexp.num_bits() < 2 {
+            return Err(ErrorStack::raise(ffi::ERR_LIB_RSA, ffi::RSA_R_BAD_E_VALUE));
+        }
+
         unsafe {
             cfg_if! {
                 if #[cfg(ossl300)] {
@@ -588,9 +1147,29 @@ impl<T> PkeyCtxRef<T> {
         Ok(())
     }
 
-    /// Sets the RSA PSS salt length.
+    /// Like [`set_rsa_keygen_pubexp`](Self::set_rsa_keygen_pubexp), but takes the exponent as an
+    /// integer, which covers common exponents such as 65537 without building a [`BigNum`].
+    ///
+    /// The exponent must be odd and at least 3, otherwise an error is returned.
     ///
     /// This is only useful for RSA keys.
+    pub fn set_rsa_keygen_pubexp_u64(&mut self, pubexp: u64) -> Result<(), ErrorStack> {
+        if pubexp < 3 || pubexp % 2 == 0 {
+            return Err(ErrorStack::raise(ffi::ERR_LIB_RSA, ffi::RSA_R_BAD_E_VALUE));
+        }
+
+        let pubexp = BigNum::from_slice(&pubexp.to_be_bytes())?;
+        self.set_rsa_keygen_pubexp(&pubexp)
+    }
+
+    /// Sets the RSA PSS salt length.
+    ///
+    /// The special values [`RsaPssSaltlen::DIGEST_LENGTH`] and [`RsaPssSaltlen::MAXIMUM_LENGTH`]
+    /// select a length relative to the digest and key, and [`RsaPssSaltlen::custom`] an exact
+    /// number of bytes.
+    ///
+    /// This is only valid for RSA keys using PSS padding in a signing or verification operation,
+    /// so the padding must be set with [`set_rsa_padding`](Self::set_rsa_padding) first.
     #[corresponds(EVP_PKEY_CTX_set_rsa_pss_saltlen)]
     #[inline]
     pub fn set_rsa_pss_saltlen(&mut self, len: RsaPssSaltlen) -> Result<(), ErrorStack> {
@@ -603,6 +1182,26 @@ impl<T> PkeyCtxRef<T> {
         }
     }
 
+    /// Returns the RSA PSS salt length.
+    ///
+    /// The special values are returned as the matching [`RsaPssSaltlen`] constants, such as
+    /// [`RsaPssSaltlen::DIGEST_LENGTH`], and other lengths as [`RsaPssSaltlen::custom`] values.
+    ///
+    /// This is only useful for RSA keys using PSS padding in a signing or verification operation.
+    #[corresponds(EVP_PKEY_CTX_get_rsa_pss_saltlen)]
+    #[inline]
+    pub fn rsa_pss_saltlen(&self) -> Result<RsaPssSaltlen, ErrorStack> {
+        let mut len = 0;
+        unsafe {
+            cvt(ffi::EVP_PKEY_CTX_get_rsa_pss_saltlen(
+                self.as_ptr(),
+                &mut len,
+            ))?;
+        }
+
+        Ok(RsaPssSaltlen::custom(len))
+    }
+
     /// Sets the RSA MGF1 algorithm.
     ///
     /// This is only useful for RSA keys.
@@ -619,8 +1218,64 @@ impl<T> PkeyCtxRef<T> {
         Ok(())
     }
 
+    /// Configures the context for signing or verification using `scheme`.
+    ///
+    /// This sets the padding mode, signature digest, MGF1 digest and salt length as appropriate
+    /// for the scheme. An error is returned if the context's key cannot be used with the scheme.
+    /// The context must already be initialized for signing or verification.
+    pub fn configure_signature(&mut self, scheme: SignatureScheme) -> Result<(), ErrorStack> {
+        let id = self
+            .pkey()
+            .ok_or_else(|| ErrorStack::raise(ffi::ERR_LIB_EVP, ffi::EVP_R_NO_KEY_SET))?
+            .id();
+
+        match scheme {
+            SignatureScheme::RsaPkcs1Sha256 | SignatureScheme::RsaPssSha256 => {
+                if id != Id::RSA {
+                    return Err(ErrorStack::raise(
+                        ffi::ERR_LIB_EVP,
+                        ffi::EVP_R_EXPECTING_AN_RSA_KEY,
+                    ));
+                }
+                if scheme == SignatureScheme::RsaPkcs1Sha256 {
+                    self.set_rsa_padding(Padding::PKCS1)?;
+                    self.set_signature_md(Md::sha256())?;
+                } else {
+                    self.set_rsa_padding(Padding::PKCS1_PSS)?;
+                    self.set_signature_md(Md::sha256())?;
+                    self.set_rsa_mgf1_md(Md::sha256())?;
+                    self.set_rsa_pss_saltlen(RsaPssSaltlen::DIGEST_LENGTH)?;
+                }
+            }
+            SignatureScheme::EcdsaSha256 => {
+                if id != Id::EC {
+                    return Err(ErrorStack::raise(
+                        ffi::ERR_LIB_EVP,
+                        ffi::EVP_R_EXPECTING_A_EC_KEY,
+                    ));
+                }
+                self.set_signature_md(Md::sha256())?;
+            }
+            #[cfg(any(ossl111, boringssl, libressl370, awslc))]
+            SignatureScheme::Ed25519 => {
+                if id != Id::ED25519 {
+                    return Err(ErrorStack::raise(
+                        ffi::ERR_LIB_EVP,
+                        ffi::EVP_R_OPERATION_NOT_SUPPORTED_FOR_THIS_KEYTYPE,
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     /// Sets the RSA OAEP algorithm.
     ///
+    /// Any digest can be used, including the SHA-3 family such as [`Md::sha3_256`] on OpenSSL
+    /// 1.1.1 and newer. The MGF1 digest is set separately with
+    /// [`set_rsa_mgf1_md`](Self::set_rsa_mgf1_md).
+    ///
     /// This is only useful for RSA keys.
     #[corresponds(EVP_PKEY_CTX_set_rsa_oaep_md)]
     #[inline]
@@ -635,8 +1290,46 @@ impl<T> PkeyCtxRef<T> {
         Ok(())
     }
 
+    /// Returns the RSA OAEP digest in use.
+    ///
+    /// This is only useful for RSA keys.
+    #[corresponds(EVP_PKEY_CTX_get_rsa_oaep_md)]
+    #[inline]
+    pub fn rsa_oaep_md(&self) -> Result<&MdRef, ErrorStack> {
+        let mut md = ptr::null();
+        unsafe {
+            cvt(ffi::EVP_PKEY_CTX_get_rsa_oaep_md(self.as_ptr(), &mut md))?;
+            MdRef::from_const_ptr_opt(md)
+        }
+        .ok_or_else(|| ErrorStack::raise(ffi::ERR_LIB_EVP, ffi::EVP_R_UNKNOWN_DIGEST))
+    }
+
+    /// Sets both the RSA OAEP and MGF1 digests to `md`.
+    ///
+    /// Using the same digest for both is the common configuration, and a mismatch between the
+    /// two sides (e.g. SHA-256 OAEP with the default SHA-1 MGF1) makes decryption fail. Only call
+    /// [`set_rsa_oaep_md`][Self::set_rsa_oaep_md] and [`set_rsa_mgf1_md`][Self::set_rsa_mgf1_md]
+    /// separately when interoperating with a peer that is known to use different digests.
+    ///
+    /// This is only useful for RSA keys.
+    #[corresponds(EVP_PKEY_CTX_set_rsa_oaep_md)]
+    #[inline]
+    pub fn set_rsa_oaep_and_mgf1_md(&mut self, md: &MdRef) -> Result<(), ErrorStack> {
+        self.set_rsa_oaep_md(md)?;
+        self.set_rsa_mgf1_md(md)
+    }
+
     /// Sets the RSA OAEP label.
     ///
+    /// The label is copied into memory owned by the context, so `label` does not need to outlive
+    /// this call. An empty label is equivalent to not setting a label, and replaces any label set
+    /// previously.
+    ///
+    /// OpenSSL frees the context's copy of the label without overwriting it, both when it is
+    /// replaced and when the context is dropped. See
+    /// [`set_rsa_oaep_label_wiping_previous`](Self::set_rsa_oaep_label_wiping_previous) if the
+    /// label is sensitive.
+    ///
     /// This is only useful for RSA keys.
     #[corresponds(EVP_PKEY_CTX_set0_rsa_oaep_label)]
     pub fn set_rsa_oaep_label(&mut self, label: &[u8]) -> Result<(), ErrorStack> {
@@ -644,8 +1337,16 @@ impl<T> PkeyCtxRef<T> {
         let len = LenType::try_from(label.len()).unwrap();
 
         unsafe {
-            let p = ffi::OPENSSL_malloc(label.len() as _);
-            ptr::copy_nonoverlapping(label.as_ptr(), p as *mut _, label.len());
+            // OpenSSL 3 rejects a null label even if it is empty, but older versions only take
+            // ownership of non-empty labels.
+            let p = if label.is_empty() && !cfg!(ossl300) {
+                ptr::null_mut()
+            } else {
+                cvt_p(ffi::OPENSSL_malloc(label.len().max(1) as _))?
+            };
+            if !label.is_empty() {
+                ptr::copy_nonoverlapping(label.as_ptr(), p as *mut u8, label.len());
+            }
 
             let r = cvt(ffi::EVP_PKEY_CTX_set0_rsa_oaep_label(
                 self.as_ptr(),
@@ -661,8 +1362,69 @@ impl<T> PkeyCtxRef<T> {
         Ok(())
     }
 
-    /// Sets the cipher used during key generation.
-    #[cfg(not(any(boringssl, awslc)))]
+    /// Like [`set_rsa_oaep_label`](Self::set_rsa_oaep_label), but first overwrites the label
+    /// currently held by the context with zeros.
+    ///
+    /// OpenSSL does not clear the label before freeing it, and nothing wipes the label when the
+    /// context is dropped. Call `set_rsa_oaep_label_wiping_previous(&[])` before dropping the
+    /// context to wipe the last label set.
+    ///
+    /// This only reaches the label held by the context. On OpenSSL 3, the label is passed to the
+    /// provider through a temporary copy which OpenSSL frees without wiping, so a copy of a
+    /// sensitive label may still be left behind in freed memory. Wiping `label` itself remains
+    /// the caller's responsibility.
+    ///
+    /// This is only useful for RSA keys.
+    #[corresponds(EVP_PKEY_CTX_set0_rsa_oaep_label)]
+    #[cfg(not(any(boringssl, awslc)))]
+    pub fn set_rsa_oaep_label_wiping_previous(&mut self, label: &[u8]) -> Result<(), ErrorStack> {
+        self.wipe_rsa_oaep_label()?;
+        self.set_rsa_oaep_label(label)
+    }
+
+    /// Overwrites the label held by the context, if any, with zeros.
+    #[cfg(not(any(boringssl, awslc)))]
+    fn wipe_rsa_oaep_label(&mut self) -> Result<(), ErrorStack> {
+        unsafe {
+            let mut p = ptr::null_mut();
+            let len = cvt_n(ffi::EVP_PKEY_CTX_get0_rsa_oaep_label(self.as_ptr(), &mut p))?;
+            if len > 0 && !p.is_null() {
+                ffi::OPENSSL_cleanse(p as *mut c_void, len as usize);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Configures the context for RSA-OAEP using `config`.
+    ///
+    /// The padding mode is set to OAEP, followed by both digests and the label, which is the order
+    /// OpenSSL requires. The configuration is validated first, so nothing is changed if the key is
+    /// too small to hold any message with the OAEP digest, or the label is longer than OpenSSL
+    /// accepts. The context must already have been initialized for encryption or decryption.
+    ///
+    /// This is only useful for RSA keys.
+    pub fn apply_rsa_oaep(&mut self, config: &RsaOaepConfig<'_>) -> Result<(), ErrorStack> {
+        let k = self
+            .rsa_size()
+            .ok_or_else(|| ErrorStack::raise(ffi::ERR_LIB_EVP, ffi::EVP_R_EXPECTING_AN_RSA_KEY))?;
+        if k < 2 * config.oaep_md.size() + 2 {
+            return Err(ErrorStack::raise(
+                ffi::ERR_LIB_RSA,
+                ffi::RSA_R_KEY_SIZE_TOO_SMALL,
+            ));
+        }
+        let label = config.label.unwrap_or(&[]);
+        check_rsa_oaep_label_len(label.len())?;
+
+        self.set_rsa_padding(Padding::PKCS1_OAEP)?;
+        self.set_rsa_oaep_md(config.oaep_md)?;
+        self.set_rsa_mgf1_md(config.mgf1_md)?;
+        self.set_rsa_oaep_label(label)
+    }
+
+    /// Sets the cipher used during key generation.
+    #[cfg(not(any(boringssl, awslc)))]
     #[corresponds(EVP_PKEY_CTX_ctrl)]
     #[inline]
     pub fn set_keygen_cipher(&mut self, cipher: &CipherRef) -> Result<(), ErrorStack> {
@@ -816,6 +1578,275 @@ impl<T> PkeyCtxRef<T> {
         Ok(())
     }
 
+    /// Sets the digest used by the TLS PRF.
+    ///
+    /// TLS 1.2 uses the cipher suite's digest, while TLS 1.0 and 1.1 use the combined MD5-SHA1
+    /// digest, which can be fetched with [`Md::fetch`] as `"MD5-SHA1"` on OpenSSL 3.
+    ///
+    /// Requires OpenSSL 1.1.0 or newer.
+    #[corresponds(EVP_PKEY_CTX_set_tls1_prf_md)]
+    #[cfg(ossl110)]
+    #[inline]
+    pub fn set_tls1_prf_md(&mut self, md: &MdRef) -> Result<(), ErrorStack> {
+        unsafe {
+            cvt(ffi::EVP_PKEY_CTX_set_tls1_prf_md(
+                self.as_ptr(),
+                md.as_ptr(),
+            ))?;
+        }
+
+        Ok(())
+    }
+
+    /// Sets the secret for the TLS PRF, replacing any secret set previously.
+    ///
+    /// This also clears any seed added with [`add_tls1_prf_seed`](Self::add_tls1_prf_seed).
+    ///
+    /// Requires OpenSSL 1.1.0 or newer.
+    #[corresponds(EVP_PKEY_CTX_set1_tls1_prf_secret)]
+    #[cfg(ossl110)]
+    #[inline]
+    pub fn set_tls1_prf_secret(&mut self, secret: &[u8]) -> Result<(), ErrorStack> {
+        let len = c_int::try_from(secret.len()).unwrap();
+
+        unsafe {
+            cvt(ffi::EVP_PKEY_CTX_set1_tls1_prf_secret(
+                self.as_ptr(),
+                secret.as_ptr(),
+                len,
+            ))?;
+        }
+
+        Ok(())
+    }
+
+    /// Appends to the seed for the TLS PRF.
+    ///
+    /// The seed is the concatenation of every call, so the label and the random values can be
+    /// added separately.
+    ///
+    /// Requires OpenSSL 1.1.0 or newer.
+    #[corresponds(EVP_PKEY_CTX_add1_tls1_prf_seed)]
+    #[cfg(ossl110)]
+    #[inline]
+    pub fn add_tls1_prf_seed(&mut self, seed: &[u8]) -> Result<(), ErrorStack> {
+        let len = c_int::try_from(seed.len()).unwrap();
+
+        unsafe {
+            cvt(ffi::EVP_PKEY_CTX_add1_tls1_prf_seed(
+                self.as_ptr(),
+                seed.as_ptr(),
+                len,
+            ))?;
+        }
+
+        Ok(())
+    }
+
+    /// Sets the password for scrypt derivation.
+    ///
+    /// Requires OpenSSL 1.1.0 or newer.
+    #[corresponds(EVP_PKEY_CTX_set1_pbe_pass)]
+    #[cfg(ossl110)]
+    #[inline]
+    pub fn set_scrypt_pass(&mut self, pass: &[u8]) -> Result<(), ErrorStack> {
+        let len = c_int::try_from(pass.len()).unwrap();
+
+        unsafe {
+            cvt(ffi::EVP_PKEY_CTX_set1_pbe_pass(
+                self.as_ptr(),
+                pass.as_ptr() as *const _,
+                len,
+            ))?;
+        }
+
+        Ok(())
+    }
+
+    /// Sets the salt for scrypt derivation.
+    ///
+    /// Requires OpenSSL 1.1.0 or newer.
+    #[corresponds(EVP_PKEY_CTX_set1_scrypt_salt)]
+    #[cfg(ossl110)]
+    #[inline]
+    pub fn set_scrypt_salt(&mut self, salt: &[u8]) -> Result<(), ErrorStack> {
+        let len = c_int::try_from(salt.len()).unwrap();
+
+        unsafe {
+            cvt(ffi::EVP_PKEY_CTX_set1_scrypt_salt(
+                self.as_ptr(),
+                salt.as_ptr(),
+                len,
+            ))?;
+        }
+
+        Ok(())
+    }
+
+    /// Sets the scrypt CPU/memory cost parameter `N`, which must be a power of two greater than 1.
+    ///
+    /// Requires OpenSSL 1.1.0 or newer.
+    #[corresponds(EVP_PKEY_CTX_set_scrypt_N)]
+    #[cfg(ossl110)]
+    #[inline]
+    pub fn set_scrypt_n(&mut self, n: u64) -> Result<(), ErrorStack> {
+        unsafe {
+            cvt(ffi::EVP_PKEY_CTX_set_scrypt_N(self.as_ptr(), n))?;
+        }
+
+        Ok(())
+    }
+
+    /// Sets the scrypt block size parameter `r`.
+    ///
+    /// Requires OpenSSL 1.1.0 or newer.
+    #[corresponds(EVP_PKEY_CTX_set_scrypt_r)]
+    #[cfg(ossl110)]
+    #[inline]
+    pub fn set_scrypt_r(&mut self, r: u64) -> Result<(), ErrorStack> {
+        unsafe {
+            cvt(ffi::EVP_PKEY_CTX_set_scrypt_r(self.as_ptr(), r))?;
+        }
+
+        Ok(())
+    }
+
+    /// Sets the scrypt parallelization parameter `p`.
+    ///
+    /// Requires OpenSSL 1.1.0 or newer.
+    #[corresponds(EVP_PKEY_CTX_set_scrypt_p)]
+    #[cfg(ossl110)]
+    #[inline]
+    pub fn set_scrypt_p(&mut self, p: u64) -> Result<(), ErrorStack> {
+        unsafe {
+            cvt(ffi::EVP_PKEY_CTX_set_scrypt_p(self.as_ptr(), p))?;
+        }
+
+        Ok(())
+    }
+
+    /// Sets the maximum amount of memory scrypt may use, in bytes.
+    ///
+    /// Derivation fails if the `N` and `r` parameters require more memory than this. OpenSSL
+    /// defaults to 32 MiB when no limit is set.
+    ///
+    /// Requires OpenSSL 1.1.0 or newer.
+    #[corresponds(EVP_PKEY_CTX_set_scrypt_maxmem_bytes)]
+    #[cfg(ossl110)]
+    #[inline]
+    pub fn set_scrypt_maxmem_bytes(&mut self, maxmem: u64) -> Result<(), ErrorStack> {
+        unsafe {
+            cvt(ffi::EVP_PKEY_CTX_set_scrypt_maxmem_bytes(
+                self.as_ptr(),
+                maxmem,
+            ))?;
+        }
+
+        Ok(())
+    }
+
+    /// Sets the KDF applied to the shared secret of a DH key exchange.
+    ///
+    /// Unlike the ECDH X9.63 KDF, the X9.42 KDF used for DH encodes the key encryption algorithm
+    /// OID (see [`set_dh_kdf_oid`][Self::set_dh_kdf_oid]) and the output length into its input,
+    /// so both are required, and the result is meant to be used directly as a key wrapping key.
+    ///
+    /// This is only useful for DH keys, after calling [`derive_init`][Self::derive_init].
+    ///
+    /// Requires OpenSSL 3.0.0 or newer.
+    #[corresponds(EVP_PKEY_CTX_set_dh_kdf_type)]
+    #[cfg(ossl300)]
+    #[inline]
+    pub fn set_dh_kdf_type(&mut self, kdf: DhKdfType) -> Result<(), ErrorStack> {
+        unsafe {
+            cvt(ffi::EVP_PKEY_CTX_set_dh_kdf_type(self.as_ptr(), kdf.0))?;
+        }
+
+        Ok(())
+    }
+
+    /// Sets the digest used by the DH KDF.
+    ///
+    /// Requires OpenSSL 3.0.0 or newer.
+    #[corresponds(EVP_PKEY_CTX_set_dh_kdf_md)]
+    #[cfg(ossl300)]
+    #[inline]
+    pub fn set_dh_kdf_md(&mut self, md: &MdRef) -> Result<(), ErrorStack> {
+        unsafe {
+            cvt(ffi::EVP_PKEY_CTX_set_dh_kdf_md(self.as_ptr(), md.as_ptr()))?;
+        }
+
+        Ok(())
+    }
+
+    /// Sets the length in bytes of the key produced by the DH KDF.
+    ///
+    /// The buffer passed to [`derive`][Self::derive] must be exactly this long.
+    ///
+    /// Requires OpenSSL 3.0.0 or newer.
+    #[corresponds(EVP_PKEY_CTX_set_dh_kdf_outlen)]
+    #[cfg(ossl300)]
+    #[inline]
+    pub fn set_dh_kdf_outlen(&mut self, len: usize) -> Result<(), ErrorStack> {
+        let len = c_int::try_from(len).unwrap();
+
+        unsafe {
+            cvt(ffi::EVP_PKEY_CTX_set_dh_kdf_outlen(self.as_ptr(), len))?;
+        }
+
+        Ok(())
+    }
+
+    /// Sets the user keying material passed to the DH KDF.
+    ///
+    /// This corresponds to the `partyAInfo` field of RFC 2631 and is optional. The ECDH
+    /// equivalent is the X9.63 shared info.
+    ///
+    /// Requires OpenSSL 3.0.0 or newer.
+    #[corresponds(EVP_PKEY_CTX_set0_dh_kdf_ukm)]
+    #[cfg(ossl300)]
+    pub fn set_dh_kdf_ukm(&mut self, ukm: &[u8]) -> Result<(), ErrorStack> {
+        let len = c_int::try_from(ukm.len()).unwrap();
+
+        unsafe {
+            let p = cvt_p(ffi::OPENSSL_malloc(ukm.len().max(1) as _))?;
+            ptr::copy_nonoverlapping(ukm.as_ptr(), p as *mut _, ukm.len());
+
+            let r = cvt(ffi::EVP_PKEY_CTX_set0_dh_kdf_ukm(
+                self.as_ptr(),
+                p as *mut _,
+                len,
+            ));
+            if r.is_err() {
+                ffi::OPENSSL_free(p);
+            }
+            r?;
+        }
+
+        Ok(())
+    }
+
+    /// Sets the OID of the key encryption algorithm the DH KDF output is intended for.
+    ///
+    /// This has no ECDH equivalent. For CMS this is the key wrap algorithm, for example
+    /// `id-aes128-wrap`.
+    ///
+    /// Requires OpenSSL 3.0.0 or newer.
+    #[corresponds(EVP_PKEY_CTX_set0_dh_kdf_oid)]
+    #[cfg(ossl300)]
+    pub fn set_dh_kdf_oid(&mut self, oid: &Asn1ObjectRef) -> Result<(), ErrorStack> {
+        unsafe {
+            let oid = cvt_p(ffi::OBJ_dup(oid.as_ptr()))?;
+            let r = cvt(ffi::EVP_PKEY_CTX_set0_dh_kdf_oid(self.as_ptr(), oid));
+            if r.is_err() {
+                ffi::ASN1_OBJECT_free(oid);
+            }
+            r?;
+        }
+
+        Ok(())
+    }
+
     /// Derives a shared secret between two keys.
     ///
     /// If `buf` is set to `None`, an upper bound on the number of bytes required for the buffer will be returned.
@@ -834,15 +1865,91 @@ impl<T> PkeyCtxRef<T> {
     }
 
     /// Like [`Self::derive`] but appends the secret to a [`Vec`].
+    ///
+    /// Some derivations, such as HKDF in extract-and-expand or expand-only mode, can produce
+    /// output of any length and so do not report a size up front. An error is returned for
+    /// those; use [`Self::derive`] with a buffer of the desired length instead.
     pub fn derive_to_vec(&mut self, buf: &mut Vec<u8>) -> Result<usize, ErrorStack> {
         let base = buf.len();
         let len = self.derive(None)?;
+        if len == usize::MAX {
+            return Err(ErrorStack::raise(
+                ffi::ERR_LIB_EVP,
+                ffi::EVP_R_INVALID_KEY_LENGTH,
+            ));
+        }
         buf.resize(base + len, 0);
         let len = self.derive(Some(&mut buf[base..]))?;
         buf.truncate(base + len);
         Ok(len)
     }
 
+    /// Derives a shared secret of exactly `N` bytes into an array.
+    ///
+    /// This suits algorithms with a fixed secret size, such as X25519 (32 bytes) and X448
+    /// (56 bytes). An error is returned if the secret is not exactly `N` bytes long.
+    pub fn derive_fixed<const N: usize>(&mut self) -> Result<[u8; N], ErrorStack> {
+        let mut buf = [0; N];
+        let len = self.derive(Some(&mut buf))?;
+        if len != N {
+            return Err(ErrorStack::raise(
+                ffi::ERR_LIB_EVP,
+                ffi::EVP_R_INVALID_KEY_LENGTH,
+            ));
+        }
+        Ok(buf)
+    }
+
+    /// Derives the 56-byte X448 shared secret.
+    ///
+    /// The context must have been set up with [`derive_init`](Self::derive_init) and
+    /// [`derive_set_peer`](Self::derive_set_peer), and an error is returned unless both the
+    /// context's key and the peer key are X448 keys.
+    ///
+    /// Requires OpenSSL 1.1.1 or newer.
+    #[cfg(ossl111)]
+    pub fn derive_x448(&mut self) -> Result<[u8; 56], ErrorStack> {
+        let peer = unsafe { ffi::EVP_PKEY_CTX_get0_peerkey(self.as_ptr()) };
+        let peer_is_x448 =
+            !peer.is_null() && unsafe { PKeyRef::<()>::from_ptr(peer) }.id() == Id::X448;
+        if !peer_is_x448 || self.pkey().map(|pkey| pkey.id()) != Some(Id::X448) {
+            return Err(ErrorStack::raise(
+                ffi::ERR_LIB_EVP,
+                ffi::EVP_R_OPERATION_NOT_SUPPORTED_FOR_THIS_KEYTYPE,
+            ));
+        }
+
+        self.derive_fixed()
+    }
+
+    /// Derives a shared secret and compares it to `expected` in constant time.
+    ///
+    /// The contents are compared with [`memcmp::eq`], so the time taken does
+    /// not depend on how much of the secret matches. Only the length of the secret can be
+    /// learned, since a secret of a different length is rejected without comparing it.
+    pub fn derive_and_verify(&mut self, expected: &[u8]) -> Result<bool, ErrorStack> {
+        let mut secret = vec![];
+        self.derive_to_vec(&mut secret)?;
+        Ok(secret.len() == expected.len() && memcmp::eq(&secret, expected))
+    }
+
+    /// Derives a key of exactly the length required by `cipher`.
+    ///
+    /// This is intended for HKDF contexts, where the output length is chosen by the caller, so
+    /// the result can be used directly as the cipher's key. An error is returned if the context
+    /// produces output of a different length, such as HKDF in extract-only mode.
+    pub fn derive_cipher_key(&mut self, cipher: &CipherRef) -> Result<Vec<u8>, ErrorStack> {
+        let mut key = vec![0; cipher.key_length()];
+        let len = self.derive(Some(&mut key))?;
+        if len != key.len() {
+            return Err(ErrorStack::raise(
+                ffi::ERR_LIB_EVP,
+                ffi::EVP_R_INVALID_KEY_LENGTH,
+            ));
+        }
+        Ok(key)
+    }
+
     /// Generates a new public/private keypair.
     #[corresponds(EVP_PKEY_keygen)]
     #[inline]
@@ -854,7 +1961,52 @@ impl<T> PkeyCtxRef<T> {
         }
     }
 
+    /// Like [`keygen`](Self::keygen), but gives up once `timeout` has elapsed.
+    ///
+    /// Generation is aborted cooperatively from OpenSSL's progress callback, so the deadline is
+    /// only checked between steps such as prime candidate tests. This bounds the time taken by
+    /// algorithms which report progress, like RSA, DSA and DH, but has no effect on the others.
+    ///
+    /// The context's application data and generation callback are replaced while the key is
+    /// generated, and restored afterwards.
+    #[corresponds(EVP_PKEY_CTX_set_cb)]
+    #[cfg(not(any(boringssl, awslc)))]
+    pub fn keygen_with_timeout(&mut self, timeout: Duration) -> Result<PKey<Private>, KeygenError> {
+        let mut deadline = KeygenDeadline {
+            deadline: Instant::now().checked_add(timeout),
+            timed_out: false,
+        };
+        let result = unsafe {
+            let app_data = ffi::EVP_PKEY_CTX_get_app_data(self.as_ptr());
+            let cb = ffi::EVP_PKEY_CTX_get_cb(self.as_ptr());
+            ffi::EVP_PKEY_CTX_set_app_data(self.as_ptr(), &mut deadline as *mut _ as *mut c_void);
+            ffi::EVP_PKEY_CTX_set_cb(self.as_ptr(), Some(keygen_deadline_cb));
+            let result = self.keygen();
+            ffi::EVP_PKEY_CTX_set_cb(self.as_ptr(), cb);
+            ffi::EVP_PKEY_CTX_set_app_data(self.as_ptr(), app_data);
+            result
+        };
+
+        match result {
+            Err(_) if deadline.timed_out => Err(KeygenError::Timeout),
+            result => Ok(result?),
+        }
+    }
+
+    /// Generates a new keypair and returns only its public half.
+    ///
+    /// This is useful when the key only needs to be shared, as the private component is freed
+    /// before returning rather than being retained by the caller.
+    pub fn keygen_public_only(&mut self) -> Result<PKey<Public>, ErrorStack> {
+        let key = self.keygen()?;
+        let der = key.public_key_to_der()?;
+        PKey::public_key_from_der(&der)
+    }
+
     /// Generates a new set of key parameters.
+    ///
+    /// Keys using the parameters can then be generated by creating a new context from the
+    /// returned [`PKey`] and calling [`keygen`](Self::keygen) on it.
     #[corresponds(EVP_PKEY_paramgen)]
     #[inline]
     pub fn paramgen(&mut self) -> Result<PKey<Params>, ErrorStack> {
@@ -865,9 +2017,30 @@ impl<T> PkeyCtxRef<T> {
         }
     }
 
+    /// Generates a new set of key parameters, then a key using them.
+    ///
+    /// This is a convenience for algorithms like DSA and DH, where a key is generated from
+    /// parameters rather than directly. The context must have been set up with
+    /// [`paramgen_init`][Self::paramgen_init] and any parameter options. It is equivalent to:
+    ///
+    /// ```ignore
+    /// let params = ctx.paramgen()?;
+    /// let mut ctx = PkeyCtx::new(&params)?;
+    /// ctx.keygen_init()?;
+    /// let key = ctx.keygen()?;
+    /// ```
+    pub fn generate_with_params(&mut self) -> Result<PKey<Private>, ErrorStack> {
+        let params = self.paramgen()?;
+        let mut ctx = PkeyCtx::new(&params)?;
+        ctx.keygen_init()?;
+        ctx.keygen()
+    }
+
     /// Sets the nonce type for a private key context.
     ///
     /// The nonce for DSA and ECDSA can be either random (the default) or deterministic (as defined by RFC 6979).
+    /// A context which was set to [`NonceType::DETERMINISTIC_K`] can be switched back with
+    /// [`NonceType::RANDOM_K`].
     ///
     /// This is only useful for DSA and ECDSA.
     /// Requires OpenSSL 3.2.0 or newer.
@@ -887,6 +2060,38 @@ impl<T> PkeyCtxRef<T> {
         Ok(())
     }
 
+    /// Selects the EdDSA instance used for signing or verification.
+    ///
+    /// The instance must match the key type, e.g. [`EddsaInstance::ED25519PH`] for Ed25519 keys.
+    /// With the prehash instances, the message passed to
+    /// [`MdCtxRef::digest_sign`](crate::md_ctx::MdCtxRef::digest_sign) is hashed by OpenSSL
+    /// before signing, so large messages are not buffered by the signature algorithm itself.
+    ///
+    /// This must be called on the context returned by
+    /// [`MdCtxRef::digest_sign_init`](crate::md_ctx::MdCtxRef::digest_sign_init) or
+    /// [`MdCtxRef::digest_verify_init`](crate::md_ctx::MdCtxRef::digest_verify_init).
+    ///
+    /// This is only useful for Ed25519 and Ed448.
+    /// Requires OpenSSL 3.2.0 or newer.
+    #[cfg(ossl320)]
+    #[corresponds(EVP_PKEY_CTX_set_params)]
+    pub fn set_eddsa_instance(&mut self, instance: EddsaInstance) -> Result<(), ErrorStack> {
+        let instance_field_name = CStr::from_bytes_with_nul(b"instance\0").unwrap();
+        let name = CStr::from_bytes_with_nul(instance.0).unwrap();
+        unsafe {
+            let param_instance = ffi::OSSL_PARAM_construct_utf8_string(
+                instance_field_name.as_ptr(),
+                name.as_ptr() as *mut _,
+                0,
+            );
+            let param_end = ffi::OSSL_PARAM_construct_end();
+
+            let params = [param_instance, param_end];
+            cvt(ffi::EVP_PKEY_CTX_set_params(self.as_ptr(), params.as_ptr()))?;
+        }
+        Ok(())
+    }
+
     /// Gets the nonce type for a private key context.
     ///
     /// The nonce for DSA and ECDSA can be either random (the default) or deterministic (as defined by RFC 6979).
@@ -911,49 +2116,972 @@ impl<T> PkeyCtxRef<T> {
         }
         Ok(NonceType(nonce_type))
     }
-}
 
-#[cfg(test)]
-mod test {
-    use super::*;
-    use crate::bn::BigNum;
-    #[cfg(not(any(boringssl, awslc)))]
-    use crate::cipher::Cipher;
-    use crate::ec::{EcGroup, EcKey};
+    /// Returns the raw operation the context is currently initialized for.
+    ///
+    /// The value is one of the `EVP_PKEY_OP_*` constants from `openssl-sys`, such as
+    /// `EVP_PKEY_OP_DERIVE` after [`derive_init`][Self::derive_init], or `0` if no operation has
+    /// been initialized. The numeric values differ between OpenSSL
+    /// versions, so compare against the constants rather than literals.
+    #[corresponds(EVP_PKEY_CTX_get_operation)]
+    #[cfg(ossl110)]
+    #[inline]
+    pub fn operation_flags(&self) -> c_int {
+        unsafe { ffi::EVP_PKEY_CTX_get_operation(self.as_ptr()) }
+    }
+
+    /// Returns whether the context is implemented outside of OpenSSL's built-in software.
+    ///
+    /// This is the case if the context's key is bound to an `ENGINE`, or on OpenSSL 3.0 and newer
+    /// if the provider implementing the context is not one of `default`, `base`, `fips`, `legacy`
+    /// or `null`, for example a PKCS#11 provider fronting an HSM.
+    ///
+    /// Requires OpenSSL 1.1.0 or newer.
+    #[cfg(ossl110)]
+    pub fn is_engine_backed(&self) -> bool {
+        #[cfg(ossl300)]
+        unsafe {
+            let provider = ffi::EVP_PKEY_CTX_get0_provider(self.as_ptr());
+            if !provider.is_null() {
+                let name = CStr::from_ptr(ffi::OSSL_PROVIDER_get0_name(provider));
+                return !matches!(
+                    name.to_bytes(),
+                    b"default" | b"base" | b"fips" | b"legacy" | b"null"
+                );
+            }
+        }
+
+        #[cfg(not(any(osslconf = "OPENSSL_NO_DEPRECATED_3_0", osslconf = "OPENSSL_NO_ENGINE")))]
+        unsafe {
+            let pkey = ffi::EVP_PKEY_CTX_get0_pkey(self.as_ptr());
+            if !pkey.is_null() && !ffi::EVP_PKEY_get0_engine(pkey).is_null() {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    /// Returns a single-line description of the context's operation, padding and digest
+    /// configuration.
+    ///
+    /// The summary is intended for debug logging and never includes key material or the RSA OAEP
+    /// label. Settings which do not apply to the context's key or operation are omitted, so the
+    /// result may be empty. The operation, as returned by
+    /// [`operation_flags`][Self::operation_flags], is only included on OpenSSL 1.1.0 and newer.
+    pub fn config_summary(&self) -> String {
+        let mut parts = vec![];
+
+        #[cfg(ossl110)]
+        {
+            let op = match self.operation_flags() {
+                0 => None,
+                ffi::EVP_PKEY_OP_PARAMGEN => Some("paramgen"),
+                ffi::EVP_PKEY_OP_KEYGEN => Some("keygen"),
+                ffi::EVP_PKEY_OP_SIGN => Some("sign"),
+                ffi::EVP_PKEY_OP_VERIFY => Some("verify"),
+                ffi::EVP_PKEY_OP_VERIFYRECOVER => Some("verify_recover"),
+                ffi::EVP_PKEY_OP_ENCRYPT => Some("encrypt"),
+                ffi::EVP_PKEY_OP_DECRYPT => Some("decrypt"),
+                ffi::EVP_PKEY_OP_DERIVE => Some("derive"),
+                _ => Some("other"),
+            };
+            if let Some(op) = op {
+                parts.push(format!("op={}", op));
+            }
+        }
+
+        if let Ok(padding) = self.rsa_padding() {
+            let name = match padding {
+                Padding::NONE => "none",
+                Padding::PKCS1 => "pkcs1",
+                Padding::PKCS1_OAEP => "oaep",
+                Padding::PKCS1_PSS => "pss",
+                _ => "other",
+            };
+            parts.push(format!("padding={}", name));
+        }
+
+        let mds = [
+            (
+                "md",
+                self.md(|ctx, md| unsafe { ffi::EVP_PKEY_CTX_get_signature_md(ctx, md) }),
+            ),
+            (
+                "oaep_md",
+                self.md(|ctx, md| unsafe { ffi::EVP_PKEY_CTX_get_rsa_oaep_md(ctx, md) }),
+            ),
+            (
+                "mgf1_md",
+                self.md(|ctx, md| unsafe { ffi::EVP_PKEY_CTX_get_rsa_mgf1_md(ctx, md) }),
+            ),
+        ];
+        for (field, md) in mds {
+            if let Some(md) = md {
+                let name = md.type_().short_name().unwrap_or("unknown");
+                parts.push(format!("{}={}", field, name));
+            }
+        }
+
+        parts.join(" ")
+    }
+
+    /// Reads a digest from the context with one of the `EVP_PKEY_CTX_get_*_md` functions.
+    fn md<F>(&self, f: F) -> Option<&MdRef>
+    where
+        F: FnOnce(*mut ffi::EVP_PKEY_CTX, *mut *const ffi::EVP_MD) -> c_int,
+    {
+        let mut md = ptr::null();
+        cvt(f(self.as_ptr(), &mut md)).ok()?;
+        unsafe { MdRef::from_const_ptr_opt(md) }
+    }
+}
+
+/// Checks that an RSA OAEP label of `len` bytes can be passed to OpenSSL.
+fn check_rsa_oaep_label_len(len: usize) -> Result<(), ErrorStack> {
+    if crate::LenType::try_from(len).is_err() {
+        return Err(ErrorStack::raise(
+            ffi::ERR_LIB_RSA,
+            ffi::RSA_R_DATA_TOO_LARGE,
+        ));
+    }
+
+    Ok(())
+}
+
+/// Runs HKDF-Extract on `ikm`, returning the pseudorandom key (PRK).
+///
+/// The PRK is as long as the output of `md`. Together with [`hkdf_expand_many`], this splits HKDF
+/// into its two steps, so the intermediate PRK can be compared against expected values such as
+/// those published in RFC 5869 when diagnosing interoperability problems. If `salt` is `None`, a
+/// string of zeros as long as the output of `md` is used, as the RFC specifies.
+///
+/// Requires OpenSSL 1.1.1 or newer.
+#[cfg(any(ossl111, libressl360))]
+pub fn hkdf_extract(md: &MdRef, salt: Option<&[u8]>, ikm: &[u8]) -> Result<Vec<u8>, ErrorStack> {
+    let mut ctx = PkeyCtx::new_id(Id::HKDF)?;
+    ctx.derive_init()?;
+    ctx.set_hkdf_mode(HkdfMode::EXTRACT_ONLY)?;
+    ctx.set_hkdf_md(md)?;
+    ctx.set_hkdf_key(ikm)?;
+    if let Some(salt) = salt {
+        ctx.set_hkdf_salt(salt)?;
+    }
+
+    let mut prk = vec![];
+    ctx.derive_to_vec(&mut prk)?;
+    Ok(prk)
+}
+
+/// Derives several keys from one HKDF pseudorandom key.
+///
+/// Each entry of `labels` is an info label and the length of the key to derive with it. The keys
+/// are derived independently with HKDF-Expand, so each output is identical to a separate
+/// expand-only derivation with the same label, and is returned in the order of `labels`.
+///
+/// Requires OpenSSL 1.1.1 or newer.
+#[cfg(any(ossl111, libressl360))]
+pub fn hkdf_expand_many(
+    prk: &[u8],
+    md: &MdRef,
+    labels: &[(&[u8], usize)],
+) -> Result<Vec<Vec<u8>>, ErrorStack> {
+    labels
+        .iter()
+        .map(|&(info, len)| {
+            let mut ctx = PkeyCtx::new_id(Id::HKDF)?;
+            ctx.derive_init()?;
+            ctx.set_hkdf_mode(HkdfMode::EXPAND_ONLY)?;
+            ctx.set_hkdf_md(md)?;
+            ctx.set_hkdf_key(prk)?;
+            ctx.add_hkdf_info(info)?;
+
+            let mut out = vec![0; len];
+            ctx.derive(Some(&mut out))?;
+            Ok(out)
+        })
+        .collect()
+}
+
+/// Generates an X25519 key pair, returning the raw `(private, public)` keys.
+///
+/// The private key is generated by OpenSSL, so it is already clamped as required by RFC 7748.
+/// Both halves can be loaded again with [`PKey::private_key_from_raw_bytes`] and
+/// [`PKey::public_key_from_raw_bytes`].
+#[cfg(any(ossl111, boringssl, libressl370, awslc))]
+pub fn generate_x25519_raw() -> Result<([u8; 32], [u8; 32]), ErrorStack> {
+    let mut ctx = PkeyCtx::new_id(Id::X25519)?;
+    ctx.keygen_init()?;
+    let key = ctx.keygen()?;
+
+    let mut private = [0; 32];
+    private.copy_from_slice(&key.raw_private_key()?);
+    let mut public = [0; 32];
+    public.copy_from_slice(&key.raw_public_key()?);
+    Ok((private, public))
+}
+
+/// Generates `count` keys of type `id`, spreading the work across the available cores.
+///
+/// This spawns up to one thread per core, each of which creates its own context with
+/// [`PkeyCtx::new_id`], initializes it for key generation and passes it to `params` to set options
+/// such as the curve or key size. The first error reported by any thread is returned.
+pub fn generate_many<F>(id: Id, params: F, count: usize) -> Result<Vec<PKey<Private>>, ErrorStack>
+where
+    F: Fn(&mut PkeyCtxRef<()>) -> Result<(), ErrorStack> + Sync,
+{
+    let threads = thread::available_parallelism()
+        .map_or(1, |n| n.get())
+        .min(count);
+    if threads == 0 {
+        return Ok(vec![]);
+    }
+
+    let params = &params;
+    thread::scope(|scope| {
+        let handles = (0..threads)
+            .map(|i| {
+                let share = count / threads + usize::from(i < count % threads);
+                scope.spawn(move || {
+                    let mut ctx = PkeyCtx::new_id(id)?;
+                    ctx.keygen_init()?;
+                    params(&mut ctx)?;
+                    (0..share)
+                        .map(|_| ctx.keygen())
+                        .collect::<Result<Vec<_>, _>>()
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let mut keys = Vec::with_capacity(count);
+        for handle in handles {
+            let share = handle.join().unwrap_or_else(|e| panic::resume_unwind(e));
+            keys.extend(share?);
+        }
+        Ok(keys)
+    })
+}
+
+#[cfg(not(any(boringssl, awslc)))]
+struct KeygenDeadline {
+    deadline: Option<Instant>,
+    timed_out: bool,
+}
+
+#[cfg(not(any(boringssl, awslc)))]
+unsafe extern "C" fn keygen_deadline_cb(ctx: *mut ffi::EVP_PKEY_CTX) -> c_int {
+    let state = &mut *(ffi::EVP_PKEY_CTX_get_app_data(ctx) as *mut KeygenDeadline);
+    if state
+        .deadline
+        .is_some_and(|deadline| Instant::now() >= deadline)
+    {
+        state.timed_out = true;
+        return 0;
+    }
+    1
+}
+
+/// A message encrypted by [`ecies_seal`].
+#[cfg(any(ossl110, boringssl, libressl360, awslc))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EciesOutput {
+    /// The sender's ephemeral public key, encoded as an uncompressed SEC 1 point.
+    pub ephemeral_public_key: Vec<u8>,
+    /// The AES-GCM initialization vector.
+    pub iv: [u8; 12],
+    /// The AES-GCM authentication tag.
+    pub tag: [u8; 16],
+    /// The encrypted message.
+    pub ciphertext: Vec<u8>,
+}
+
+/// Encrypts `plaintext` to the EC public key `recipient` using an ECIES-like scheme.
+///
+/// An ephemeral key is generated on the recipient's curve and ECDH is performed with it. The
+/// shared secret is passed through HKDF-SHA256 with an empty salt and the encoded ephemeral
+/// public key as the info, producing a 256-bit key which encrypts `plaintext` with AES-256-GCM.
+/// `aad` is authenticated but not encrypted, and must be passed to [`ecies_open`] unchanged.
+///
+/// Every call generates a fresh ephemeral key and a fresh 96-bit IV, both drawn from OpenSSL's
+/// CSPRNG (see [`rand_bytes`]), so no key and IV pair is ever reused. Debug builds additionally
+/// assert that the IV differs from the one used by the previous call on the same thread.
+#[cfg(any(ossl110, boringssl, libressl360, awslc))]
+pub fn ecies_seal<T>(
+    recipient: &PKeyRef<T>,
+    plaintext: &[u8],
+    aad: &[u8],
+) -> Result<EciesOutput, ErrorStack>
+where
+    T: HasPublic,
+{
+    let recipient_ec = recipient.ec_key()?;
+    let group = recipient_ec.group();
+    let ephemeral = EcKey::generate(group)?;
+    let mut bn_ctx = BigNumContext::new()?;
+    let ephemeral_public_key =
+        ephemeral
+            .public_key()
+            .to_bytes(group, PointConversionForm::UNCOMPRESSED, &mut bn_ctx)?;
+    let ephemeral = PKey::from_ec_key(ephemeral)?;
+
+    let key = ecies_key(&ephemeral, recipient, &ephemeral_public_key)?;
+    let mut iv = [0; 12];
+    rand_bytes(&mut iv)?;
+    #[cfg(debug_assertions)]
+    ECIES_LAST_IV.with(|last| assert_ne!(last.replace(Some(iv)), Some(iv), "ECIES IV reused"));
+    let mut tag = [0; 16];
+    let ciphertext = symm::encrypt_aead(
+        symm::Cipher::aes_256_gcm(),
+        &key,
+        Some(&iv),
+        aad,
+        plaintext,
+        &mut tag,
+    )?;
+
+    Ok(EciesOutput {
+        ephemeral_public_key,
+        iv,
+        tag,
+        ciphertext,
+    })
+}
+
+#[cfg(all(debug_assertions, any(ossl110, boringssl, libressl360, awslc)))]
+thread_local! {
+    static ECIES_LAST_IV: Cell<Option<[u8; 12]>> = const { Cell::new(None) };
+}
+
+/// Decrypts a message produced by [`ecies_seal`] with the recipient's private key.
+///
+/// An error is returned if the message or `aad` was modified, or if it was encrypted to a
+/// different key.
+#[cfg(any(ossl110, boringssl, libressl360, awslc))]
+pub fn ecies_open<T>(
+    recipient: &PKeyRef<T>,
+    sealed: &EciesOutput,
+    aad: &[u8],
+) -> Result<Vec<u8>, ErrorStack>
+where
+    T: HasPrivate,
+{
+    let recipient_ec = recipient.ec_key()?;
+    let group = recipient_ec.group();
+    let mut bn_ctx = BigNumContext::new()?;
+    let point = EcPoint::from_bytes(group, &sealed.ephemeral_public_key, &mut bn_ctx)?;
+    let ephemeral = PKey::from_ec_key(EcKey::from_public_key(group, &point)?)?;
+
+    let key = ecies_key(recipient, &ephemeral, &sealed.ephemeral_public_key)?;
+    symm::decrypt_aead(
+        symm::Cipher::aes_256_gcm(),
+        &key,
+        Some(&sealed.iv),
+        aad,
+        &sealed.ciphertext,
+        &sealed.tag,
+    )
+}
+
+/// Derives the AES-256-GCM key for [`ecies_seal`] and [`ecies_open`].
+#[cfg(any(ossl110, boringssl, libressl360, awslc))]
+fn ecies_key<T, U>(
+    private: &PKeyRef<T>,
+    peer: &PKeyRef<U>,
+    ephemeral_public_key: &[u8],
+) -> Result<[u8; 32], ErrorStack>
+where
+    T: HasPrivate,
+    U: HasPublic,
+{
+    let mut ctx = PkeyCtx::new(private)?;
+    ctx.derive_init()?;
+    ctx.derive_set_peer(peer)?;
+    let mut secret = vec![];
+    ctx.derive_to_vec(&mut secret)?;
+
+    let mut ctx = PkeyCtx::new_id(Id::HKDF)?;
+    ctx.derive_init()?;
+    ctx.set_hkdf_md(Md::sha256())?;
+    ctx.set_hkdf_key(&secret)?;
+    ctx.add_hkdf_info(ephemeral_public_key)?;
+    let mut key = [0; 32];
+    ctx.derive(Some(&mut key))?;
+    Ok(key)
+}
+
+/// A session key and key confirmation tags produced by [`derive_confirmed`].
+///
+/// The session key is overwritten with zeros when this is dropped.
+#[cfg(any(ossl110, boringssl, libressl360, awslc))]
+pub struct ConfirmedSecret {
+    key: [u8; 32],
+    /// This side's HMAC-SHA256 key confirmation tag over the transcript, to be sent to the peer.
+    pub tag: [u8; 32],
+    peer_tag: [u8; 32],
+}
+
+#[cfg(any(ossl110, boringssl, libressl360, awslc))]
+impl ConfirmedSecret {
+    /// Returns the derived session key.
+    pub fn key(&self) -> &[u8; 32] {
+        &self.key
+    }
+
+    /// Checks the key confirmation tag received from the peer.
+    ///
+    /// The peer's tag is computed under the opposite role, so this side's own tag reflected back
+    /// by the peer is rejected. The tags are compared with [`memcmp::eq`], so the time taken does
+    /// not depend on how much of the tag matches.
+    pub fn verify_tag(&self, tag: &[u8]) -> bool {
+        tag.len() == self.peer_tag.len() && memcmp::eq(&self.peer_tag, tag)
+    }
+}
+
+#[cfg(any(ossl110, boringssl, libressl360, awslc))]
+impl Drop for ConfirmedSecret {
+    fn drop(&mut self) {
+        unsafe { ffi::OPENSSL_cleanse(self.key.as_mut_ptr() as *mut c_void, self.key.len()) };
+    }
+}
+
+/// Performs a key agreement and computes key confirmation tags over `transcript`.
+///
+/// The ECDH (or other key agreement) shared secret between `private` and `peer` is passed through
+/// HKDF-SHA256 with an empty salt and the info `"key confirmation"`, producing a 256-bit session
+/// key followed by a 256-bit MAC key, which is not returned. Each side's tag is the HMAC-SHA256
+/// of its role label, `"initiator"` or `"responder"`, followed by `transcript` under the MAC key.
+/// Exactly one of the two parties must pass `initiator = true`. When both use the same
+/// transcript, such as the concatenation of their public keys and any handshake messages, they
+/// derive the same key, and exchanging tags confirms that the peer holds the expected key. The
+/// shared secret and the intermediate key material are overwritten with zeros before returning.
+#[cfg(any(ossl110, boringssl, libressl360, awslc))]
+pub fn derive_confirmed<T, U>(
+    private: &PKeyRef<T>,
+    peer: &PKeyRef<U>,
+    transcript: &[u8],
+    initiator: bool,
+) -> Result<ConfirmedSecret, ErrorStack>
+where
+    T: HasPrivate,
+    U: HasPublic,
+{
+    let mut secret = vec![];
+    let mut okm = [0; 64];
+    let result = (|| {
+        let mut ctx = PkeyCtx::new(private)?;
+        ctx.derive_init()?;
+        ctx.derive_set_peer(peer)?;
+        ctx.derive_to_vec(&mut secret)?;
+
+        let mut ctx = PkeyCtx::new_id(Id::HKDF)?;
+        ctx.derive_init()?;
+        ctx.set_hkdf_md(Md::sha256())?;
+        ctx.set_hkdf_key(&secret)?;
+        ctx.add_hkdf_info(b"key confirmation")?;
+        ctx.derive(Some(&mut okm))?;
+
+        let mac_key = PKey::hmac(&okm[32..])?;
+        let tag = |label: &[u8]| -> Result<[u8; 32], ErrorStack> {
+            let mut signer = Signer::new(MessageDigest::sha256(), &mac_key)?;
+            signer.update(label)?;
+            signer.update(transcript)?;
+            let mut tag = [0; 32];
+            signer.sign(&mut tag)?;
+            Ok(tag)
+        };
+        let (own, theirs): (&[u8], &[u8]) = if initiator {
+            (b"initiator", b"responder")
+        } else {
+            (b"responder", b"initiator")
+        };
+
+        let mut confirmed = ConfirmedSecret {
+            key: [0; 32],
+            tag: tag(own)?,
+            peer_tag: tag(theirs)?,
+        };
+        confirmed.key.copy_from_slice(&okm[..32]);
+        Ok(confirmed)
+    })();
+
+    unsafe {
+        ffi::OPENSSL_cleanse(secret.as_mut_ptr() as *mut c_void, secret.len());
+        ffi::OPENSSL_cleanse(okm.as_mut_ptr() as *mut c_void, okm.len());
+    }
+    result
+}
+
+/// Verifies `sig` over `tbs` with a public key, looking up the digest by name.
+///
+/// `tbs` is hashed with the digest named by `md_name` (e.g. `"SHA256"`), which is also set as
+/// the signature digest. `padding` is applied for RSA keys and must be `None` for other key types.
+///
+/// Returns `Ok(false)` if the signature does not match. Errors are returned if the digest is
+/// unknown, the context could not be set up for the key, or the verification could not be
+/// performed at all, as by [`PkeyCtxRef::verify`].
+pub fn verify_signature<T>(
+    pkey: &PKeyRef<T>,
+    md_name: &str,
+    padding: Option<Padding>,
+    tbs: &[u8],
+    sig: &[u8],
+) -> Result<bool, ErrorStack>
+where
+    T: HasPublic,
+{
+    let digest = MessageDigest::from_name(md_name)
+        .ok_or_else(|| ErrorStack::raise(ffi::ERR_LIB_EVP, ffi::EVP_R_UNKNOWN_DIGEST))?;
+    let md = Md::from_nid(digest.type_())
+        .ok_or_else(|| ErrorStack::raise(ffi::ERR_LIB_EVP, ffi::EVP_R_UNKNOWN_DIGEST))?;
+    let hash = hash(digest, tbs)?;
+
+    let mut ctx = PkeyCtx::new(pkey)?;
+    ctx.verify_init()?;
+    if let Some(padding) = padding {
+        ctx.set_rsa_padding(padding)?;
+    }
+    ctx.set_signature_md(md)?;
+
+    ctx.verify(&hash, sig)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[cfg(ossl300)]
+    use crate::asn1::Asn1Object;
+    use crate::bn::BigNum;
+    use crate::cipher::Cipher;
+    use crate::ec::{EcGroup, EcKey};
     use crate::hash::{hash, MessageDigest};
     use crate::md::Md;
     use crate::nid::Nid;
     use crate::pkey::PKey;
     use crate::rsa::Rsa;
-    use crate::sign::Verifier;
+    use crate::sign::{Signer, Verifier};
     #[cfg(not(boringssl))]
     use cfg_if::cfg_if;
 
     #[test]
-    fn rsa() {
+    #[cfg(not(feature = "no-rsa-pkcs1-encrypt"))]
+    fn rsa() {
+        let key = include_bytes!("../test/rsa.pem");
+        let rsa = Rsa::private_key_from_pem(key).unwrap();
+        let pkey = PKey::from_rsa(rsa).unwrap();
+
+        let mut ctx = PkeyCtx::new(&pkey).unwrap();
+        ctx.encrypt_init().unwrap();
+        ctx.set_rsa_padding(Padding::PKCS1).unwrap();
+
+        let pt = "hello world".as_bytes();
+        let mut ct = vec![];
+        ctx.encrypt_to_vec(pt, &mut ct).unwrap();
+
+        ctx.decrypt_init().unwrap();
+        ctx.set_rsa_padding(Padding::PKCS1).unwrap();
+
+        let mut out = vec![];
+        ctx.decrypt_to_vec(&ct, &mut out).unwrap();
+
+        assert_eq!(pt, out);
+    }
+
+    #[test]
+    fn generate_many() {
+        let keys = super::generate_many(
+            Id::EC,
+            |ctx| ctx.set_ec_paramgen_curve_nid(Nid::X9_62_PRIME256V1),
+            8,
+        )
+        .unwrap();
+        assert_eq!(keys.len(), 8);
+
+        let mut ders = keys
+            .iter()
+            .map(|key| {
+                key.ec_key().unwrap().check_key().unwrap();
+                key.private_key_to_der().unwrap()
+            })
+            .collect::<Vec<_>>();
+        ders.sort();
+        ders.dedup();
+        assert_eq!(ders.len(), 8);
+
+        assert!(super::generate_many(Id::EC, |_| Ok(()), 2).is_err());
+    }
+
+    #[test]
+    fn decrypt_into() {
+        let key = include_bytes!("../test/rsa.pem");
+        let rsa = Rsa::private_key_from_pem(key).unwrap();
+        let pkey = PKey::from_rsa(rsa).unwrap();
+
+        let mut ctx = PkeyCtx::new(&pkey).unwrap();
+        ctx.encrypt_init().unwrap();
+        ctx.set_rsa_padding(Padding::PKCS1_OAEP).unwrap();
+        let mut ct1 = vec![];
+        ctx.encrypt_to_vec(b"hello world", &mut ct1).unwrap();
+        let mut ct2 = vec![];
+        ctx.encrypt_to_vec(b"goodbye", &mut ct2).unwrap();
+
+        ctx.decrypt_init().unwrap();
+        ctx.set_rsa_padding(Padding::PKCS1_OAEP).unwrap();
+        let mut expected = vec![];
+        ctx.decrypt_to_vec(&ct1, &mut expected).unwrap();
+
+        let mut out = Vec::with_capacity(ct1.len());
+        let ptr = out.as_ptr();
+        let len = ctx.decrypt_into(&ct1, &mut out).unwrap();
+        assert_eq!(len, out.len());
+        assert_eq!(out, expected);
+        assert_eq!(out, b"hello world");
+        ctx.decrypt_into(&ct2, &mut out).unwrap();
+        assert_eq!(out, b"goodbye");
+        assert_eq!(out.as_ptr(), ptr);
+        assert_eq!(out.capacity(), ct1.len());
+
+        let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).unwrap();
+        let pkey = PKey::from_ec_key(EcKey::generate(&group).unwrap()).unwrap();
+        let mut ctx = PkeyCtx::new(&pkey).unwrap();
+        assert!(ctx.decrypt_into(&ct1, &mut out).is_err());
+        assert!(out.is_empty());
+    }
+
+    #[test]
+    #[cfg(ossl300)]
+    fn require_fips() {
+        let key = include_bytes!("../test/rsa.pem");
+        let rsa = Rsa::private_key_from_pem(key).unwrap();
+        let pkey = PKey::from_rsa(rsa).unwrap();
+
+        let fips = unsafe {
+            ffi::OSSL_PROVIDER_available(ptr::null_mut(), b"fips\0".as_ptr() as *const _) == 1
+        };
+        let mut ctx = PkeyCtx::new(&pkey).unwrap();
+        if fips {
+            ctx.require_fips().unwrap();
+            ctx.sign_init().unwrap();
+        } else {
+            // Without a loaded FIPS provider there is no implementation to fetch.
+            let err = ctx.require_fips().unwrap_err();
+            assert!(!err.errors().is_empty());
+        }
+
+        let mut ctx = PkeyCtx::new_id(Id::RSA).unwrap();
+        assert!(ctx.require_fips().is_err());
+    }
+
+    #[test]
+    fn rsa_size() {
+        let rsa = Rsa::generate(2048).unwrap();
+        let pkey = PKey::from_rsa(rsa).unwrap();
+        let ctx = PkeyCtx::new(&pkey).unwrap();
+        assert_eq!(ctx.rsa_size(), Some(256));
+
+        let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).unwrap();
+        let key = EcKey::generate(&group).unwrap();
+        let pkey = PKey::from_ec_key(key).unwrap();
+        let ctx = PkeyCtx::new(&pkey).unwrap();
+        assert_eq!(ctx.rsa_size(), None);
+    }
+
+    #[test]
+    fn rsa_oaep() {
+        let key = include_bytes!("../test/rsa.pem");
+        let rsa = Rsa::private_key_from_pem(key).unwrap();
+        let pkey = PKey::from_rsa(rsa).unwrap();
+
+        let mut ctx = PkeyCtx::new(&pkey).unwrap();
+        ctx.encrypt_init().unwrap();
+        ctx.set_rsa_padding(Padding::PKCS1_OAEP).unwrap();
+        ctx.set_rsa_oaep_md(Md::sha256()).unwrap();
+        ctx.set_rsa_mgf1_md(Md::sha256()).unwrap();
+
+        let pt = "hello world".as_bytes();
+        let mut ct = vec![];
+        ctx.encrypt_to_vec(pt, &mut ct).unwrap();
+
+        ctx.decrypt_init().unwrap();
+        ctx.set_rsa_padding(Padding::PKCS1_OAEP).unwrap();
+        ctx.set_rsa_oaep_md(Md::sha256()).unwrap();
+        ctx.set_rsa_mgf1_md(Md::sha256()).unwrap();
+
+        let mut out = vec![];
+        ctx.decrypt_to_vec(&ct, &mut out).unwrap();
+
+        assert_eq!(pt, out);
+    }
+
+    #[test]
+    fn rsa_oaep_and_mgf1_md() {
+        let key = include_bytes!("../test/rsa.pem");
+        let rsa = Rsa::private_key_from_pem(key).unwrap();
+        let pkey = PKey::from_rsa(rsa).unwrap();
+
+        let mut ctx = PkeyCtx::new(&pkey).unwrap();
+        ctx.encrypt_init().unwrap();
+        ctx.set_rsa_padding(Padding::PKCS1_OAEP).unwrap();
+        ctx.set_rsa_oaep_and_mgf1_md(Md::sha256()).unwrap();
+
+        let pt = "hello world".as_bytes();
+        let mut ct = vec![];
+        ctx.encrypt_to_vec(pt, &mut ct).unwrap();
+
+        ctx.decrypt_init().unwrap();
+        ctx.set_rsa_padding(Padding::PKCS1_OAEP).unwrap();
+        ctx.set_rsa_oaep_md(Md::sha256()).unwrap();
+        ctx.set_rsa_mgf1_md(Md::sha256()).unwrap();
+
+        let mut out = vec![];
+        ctx.decrypt_to_vec(&ct, &mut out).unwrap();
+        assert_eq!(pt, out);
+
+        ctx.decrypt_init().unwrap();
+        ctx.set_rsa_padding(Padding::PKCS1_OAEP).unwrap();
+        ctx.set_rsa_oaep_md(Md::sha256()).unwrap();
+        ctx.set_rsa_mgf1_md(Md::sha1()).unwrap();
+
+        let mut out = vec![];
+        assert!(ctx.decrypt_to_vec(&ct, &mut out).is_err());
+    }
+
+    #[test]
+    fn rsa_signature_md_digestinfo() {
+        let key = include_bytes!("../test/rsa.pem");
+        let rsa = Rsa::private_key_from_pem(key).unwrap();
+        let pkey = PKey::from_rsa(rsa).unwrap();
+        let digest = hash(MessageDigest::sha256(), b"hello world").unwrap();
+
+        let mut ctx = PkeyCtx::new(&pkey).unwrap();
+        ctx.sign_init().unwrap();
+        ctx.set_rsa_padding(Padding::PKCS1).unwrap();
+        assert!(ctx.signature_md().is_err());
+        ctx.set_signature_md(Md::sha256()).unwrap();
+        assert_eq!(ctx.signature_md().unwrap().type_(), Nid::SHA256);
+        let mut sig = vec![];
+        ctx.sign_to_vec(&digest, &mut sig).unwrap();
+
+        // recovering without a signature digest yields the DigestInfo, which starts with the
+        // algorithm identifier of SHA-256
+        let mut ctx = PkeyCtx::new(&pkey).unwrap();
+        ctx.verify_recover_init().unwrap();
+        ctx.set_rsa_padding(Padding::PKCS1).unwrap();
+        let mut digestinfo = vec![0; pkey.size()];
+        let len = ctx.verify_recover(&sig, Some(&mut digestinfo)).unwrap();
+        digestinfo.truncate(len);
+        let mut expected = hex::decode("3031300d060960864801650304020105000420").unwrap();
+        expected.extend_from_slice(&digest);
+        assert_eq!(digestinfo, expected);
+    }
+
+    #[test]
+    fn try_clone() {
+        let key = include_bytes!("../test/rsa.pem");
+        let rsa = Rsa::private_key_from_pem(key).unwrap();
+        let pkey = PKey::from_rsa(rsa).unwrap();
+
+        let mut ctx = PkeyCtx::new(&pkey).unwrap();
+        ctx.encrypt_init().unwrap();
+        ctx.set_rsa_padding(Padding::PKCS1_OAEP).unwrap();
+        ctx.set_rsa_oaep_and_mgf1_md(Md::sha256()).unwrap();
+        let mut clone = ctx.try_clone().unwrap();
+        assert_eq!(clone.rsa_padding().unwrap(), Padding::PKCS1_OAEP);
+        assert_eq!(clone.rsa_oaep_md().unwrap().type_(), Nid::SHA256);
+
+        let pt = b"hello world";
+        let mut decrypter = PkeyCtx::new(&pkey).unwrap();
+        decrypter.decrypt_init().unwrap();
+        decrypter.set_rsa_padding(Padding::PKCS1_OAEP).unwrap();
+        decrypter.set_rsa_oaep_and_mgf1_md(Md::sha256()).unwrap();
+        for ctx in [&mut ctx, &mut clone] {
+            let mut ct = vec![];
+            ctx.encrypt_to_vec(pt, &mut ct).unwrap();
+            let mut out = vec![];
+            decrypter.decrypt_to_vec(&ct, &mut out).unwrap();
+            assert_eq!(out, pt);
+        }
+
+        // the copies are independent
+        clone.set_rsa_padding(Padding::PKCS1).unwrap();
+        assert_eq!(ctx.rsa_padding().unwrap(), Padding::PKCS1_OAEP);
+    }
+
+    #[test]
+    fn rsa_oaep_md() {
+        let key = include_bytes!("../test/rsa.pem");
+        let rsa = Rsa::private_key_from_pem(key).unwrap();
+        let pkey = PKey::from_rsa(rsa).unwrap();
+
+        let mut ctx = PkeyCtx::new(&pkey).unwrap();
+        ctx.decrypt_init().unwrap();
+        ctx.set_rsa_padding(Padding::PKCS1_OAEP).unwrap();
+        assert_eq!(ctx.rsa_oaep_md().unwrap().type_(), Nid::SHA1);
+        ctx.set_rsa_oaep_md(Md::sha256()).unwrap();
+        assert_eq!(ctx.rsa_oaep_md().unwrap().type_(), Nid::SHA256);
+    }
+
+    #[test]
+    #[cfg(ossl111)]
+    fn rsa_oaep_sha3() {
+        let rsa = Rsa::generate(3072).unwrap();
+        let pkey = PKey::from_rsa(rsa).unwrap();
+
+        let mut ctx = PkeyCtx::new(&pkey).unwrap();
+        ctx.encrypt_init().unwrap();
+        ctx.set_rsa_padding(Padding::PKCS1_OAEP).unwrap();
+        ctx.set_rsa_oaep_md(Md::sha3_256()).unwrap();
+        ctx.set_rsa_mgf1_md(Md::sha3_256()).unwrap();
+
+        let pt = "hello world".as_bytes();
+        let mut ct = vec![];
+        ctx.encrypt_to_vec(pt, &mut ct).unwrap();
+        assert_eq!(ct.len(), 384);
+
+        ctx.decrypt_init().unwrap();
+        ctx.set_rsa_padding(Padding::PKCS1_OAEP).unwrap();
+        ctx.set_rsa_oaep_md(Md::sha3_256()).unwrap();
+        ctx.set_rsa_mgf1_md(Md::sha3_256()).unwrap();
+
+        let mut out = vec![];
+        ctx.decrypt_to_vec(&ct, &mut out).unwrap();
+        assert_eq!(pt, out);
+
+        ctx.decrypt_init().unwrap();
+        ctx.set_rsa_padding(Padding::PKCS1_OAEP).unwrap();
+        ctx.set_rsa_oaep_md(Md::sha256()).unwrap();
+        ctx.set_rsa_mgf1_md(Md::sha3_256()).unwrap();
+
+        let mut out = vec![];
+        assert!(ctx.decrypt_to_vec(&ct, &mut out).is_err());
+    }
+
+    #[test]
+    #[cfg(ossl110)]
+    fn is_engine_backed() {
+        let key = include_bytes!("../test/rsa.pem");
+        let rsa = Rsa::private_key_from_pem(key).unwrap();
+        let pkey = PKey::from_rsa(rsa).unwrap();
+
+        let mut ctx = PkeyCtx::new(&pkey).unwrap();
+        assert!(!ctx.is_engine_backed());
+        ctx.sign_init().unwrap();
+        assert!(!ctx.is_engine_backed());
+
+        let ctx = PkeyCtx::new_id(Id::HKDF).unwrap();
+        assert!(!ctx.is_engine_backed());
+    }
+
+    #[test]
+    fn apply_rsa_oaep() {
+        let key = include_bytes!("../test/rsa.pem");
+        let rsa = Rsa::private_key_from_pem(key).unwrap();
+        let pkey = PKey::from_rsa(rsa).unwrap();
+        let round_trip = |encrypt: &RsaOaepConfig<'_>, decrypt: &RsaOaepConfig<'_>| {
+            let mut ctx = PkeyCtx::new(&pkey).unwrap();
+            ctx.encrypt_init().unwrap();
+            ctx.apply_rsa_oaep(encrypt).unwrap();
+            let mut ct = vec![];
+            ctx.encrypt_to_vec(b"hello world", &mut ct).unwrap();
+
+            ctx.decrypt_init().unwrap();
+            ctx.apply_rsa_oaep(decrypt).unwrap();
+            let mut out = vec![];
+            ctx.decrypt_to_vec(&ct, &mut out).map(|_| out)
+        };
+
+        let unlabeled = RsaOaepConfig {
+            oaep_md: Md::sha256(),
+            mgf1_md: Md::sha256(),
+            label: None,
+        };
+        assert_eq!(round_trip(&unlabeled, &unlabeled).unwrap(), b"hello world");
+
+        let labeled = RsaOaepConfig {
+            label: Some(b"context"),
+            ..unlabeled
+        };
+        assert_eq!(round_trip(&labeled, &labeled).unwrap(), b"hello world");
+        assert!(round_trip(&labeled, &unlabeled).is_err());
+
+        check_rsa_oaep_label_len(crate::LenType::MAX as usize).unwrap();
+        #[cfg(not(any(boringssl, awslc)))]
+        assert!(check_rsa_oaep_label_len(crate::LenType::MAX as usize + 1).is_err());
+
+        let small = PKey::from_rsa(Rsa::generate(1024).unwrap()).unwrap();
+        let mut ctx = PkeyCtx::new(&small).unwrap();
+        ctx.encrypt_init().unwrap();
+        let config = RsaOaepConfig {
+            oaep_md: Md::sha512(),
+            ..unlabeled
+        };
+        assert!(ctx.apply_rsa_oaep(&config).is_err());
+    }
+
+    #[test]
+    fn rsa_oaep_empty_label() {
+        let key = include_bytes!("../test/rsa.pem");
+        let rsa = Rsa::private_key_from_pem(key).unwrap();
+        let pkey = PKey::from_rsa(rsa).unwrap();
+
+        let mut ctx = PkeyCtx::new(&pkey).unwrap();
+        ctx.encrypt_init().unwrap();
+        ctx.set_rsa_padding(Padding::PKCS1_OAEP).unwrap();
+        ctx.set_rsa_oaep_label(&[]).unwrap();
+
+        let pt = "hello world".as_bytes();
+        let mut ct = vec![];
+        ctx.encrypt_to_vec(pt, &mut ct).unwrap();
+
+        ctx.decrypt_init().unwrap();
+        ctx.set_rsa_padding(Padding::PKCS1_OAEP).unwrap();
+
+        let mut out = vec![];
+        ctx.decrypt_to_vec(&ct, &mut out).unwrap();
+        assert_eq!(pt, out);
+    }
+
+    #[test]
+    #[cfg(not(any(boringssl, awslc)))]
+    fn rsa_oaep_label_wiping_previous() {
         let key = include_bytes!("../test/rsa.pem");
         let rsa = Rsa::private_key_from_pem(key).unwrap();
         let pkey = PKey::from_rsa(rsa).unwrap();
 
         let mut ctx = PkeyCtx::new(&pkey).unwrap();
         ctx.encrypt_init().unwrap();
-        ctx.set_rsa_padding(Padding::PKCS1).unwrap();
-
-        let pt = "hello world".as_bytes();
+        ctx.set_rsa_padding(Padding::PKCS1_OAEP).unwrap();
+        // nothing to wipe yet
+        ctx.set_rsa_oaep_label_wiping_previous(b"secret label")
+            .unwrap();
+        let pt = b"hello world";
         let mut ct = vec![];
         ctx.encrypt_to_vec(pt, &mut ct).unwrap();
 
-        ctx.decrypt_init().unwrap();
-        ctx.set_rsa_padding(Padding::PKCS1).unwrap();
+        ctx.wipe_rsa_oaep_label().unwrap();
+        let label = unsafe {
+            let mut p = ptr::null_mut();
+            let len = ffi::EVP_PKEY_CTX_get0_rsa_oaep_label(ctx.as_ptr(), &mut p);
+            assert_eq!(len, 12);
+            crate::util::from_raw_parts(p, len as usize)
+        };
+        assert_eq!(label, [0; 12]);
+        ctx.set_rsa_oaep_label_wiping_previous(&[]).unwrap();
 
+        ctx.decrypt_init().unwrap();
+        ctx.set_rsa_padding(Padding::PKCS1_OAEP).unwrap();
+        ctx.set_rsa_oaep_label_wiping_previous(b"secret label")
+            .unwrap();
         let mut out = vec![];
         ctx.decrypt_to_vec(&ct, &mut out).unwrap();
+        assert_eq!(out, pt);
 
-        assert_eq!(pt, out);
+        ctx.set_rsa_oaep_label_wiping_previous(b"another label")
+            .unwrap();
+        assert!(ctx.decrypt_to_vec(&ct, &mut vec![]).is_err());
     }
 
     #[test]
-    fn rsa_oaep() {
+    fn rsa_oaep_label_is_copied() {
         let key = include_bytes!("../test/rsa.pem");
         let rsa = Rsa::private_key_from_pem(key).unwrap();
         let pkey = PKey::from_rsa(rsa).unwrap();
@@ -961,8 +3089,10 @@ mod test {
         let mut ctx = PkeyCtx::new(&pkey).unwrap();
         ctx.encrypt_init().unwrap();
         ctx.set_rsa_padding(Padding::PKCS1_OAEP).unwrap();
-        ctx.set_rsa_oaep_md(Md::sha256()).unwrap();
-        ctx.set_rsa_mgf1_md(Md::sha256()).unwrap();
+        {
+            let label = b"short-lived label".to_vec();
+            ctx.set_rsa_oaep_label(&label).unwrap();
+        }
 
         let pt = "hello world".as_bytes();
         let mut ct = vec![];
@@ -970,13 +3100,56 @@ mod test {
 
         ctx.decrypt_init().unwrap();
         ctx.set_rsa_padding(Padding::PKCS1_OAEP).unwrap();
-        ctx.set_rsa_oaep_md(Md::sha256()).unwrap();
-        ctx.set_rsa_mgf1_md(Md::sha256()).unwrap();
+        ctx.set_rsa_oaep_label(b"short-lived label").unwrap();
 
         let mut out = vec![];
         ctx.decrypt_to_vec(&ct, &mut out).unwrap();
-
         assert_eq!(pt, out);
+
+        ctx.decrypt_init().unwrap();
+        ctx.set_rsa_padding(Padding::PKCS1_OAEP).unwrap();
+        ctx.set_rsa_oaep_label(b"another label").unwrap();
+
+        let mut out = vec![];
+        assert!(ctx.decrypt_to_vec(&ct, &mut out).is_err());
+    }
+
+    #[test]
+    fn config_summary() {
+        let key = include_bytes!("../test/rsa.pem");
+        let rsa = Rsa::private_key_from_pem(key).unwrap();
+        let pkey = PKey::from_rsa(rsa).unwrap();
+
+        let label = b"very secret label";
+        let mut ctx = PkeyCtx::new(&pkey).unwrap();
+        ctx.encrypt_init().unwrap();
+        ctx.set_rsa_padding(Padding::PKCS1_OAEP).unwrap();
+        ctx.set_rsa_oaep_md(Md::sha256()).unwrap();
+        ctx.set_rsa_mgf1_md(Md::sha384()).unwrap();
+        ctx.set_rsa_oaep_label(label).unwrap();
+
+        let summary = ctx.config_summary();
+        #[cfg(ossl110)]
+        assert!(summary.starts_with("op=encrypt "), "{}", summary);
+        assert!(summary.contains("padding=oaep"), "{}", summary);
+        assert!(summary.contains("oaep_md=SHA256"), "{}", summary);
+        assert!(summary.contains("mgf1_md=SHA384"), "{}", summary);
+        assert!(!summary.contains("secret"), "{}", summary);
+        assert!(!summary.contains(&hex::encode(label)), "{}", summary);
+        assert!(!summary.contains('\n'));
+
+        ctx.sign_init().unwrap();
+        ctx.set_rsa_padding(Padding::PKCS1_PSS).unwrap();
+        ctx.set_signature_md(Md::sha512()).unwrap();
+
+        let summary = ctx.config_summary();
+        #[cfg(ossl110)]
+        assert!(summary.starts_with("op=sign "), "{}", summary);
+        assert!(summary.contains("padding=pss"), "{}", summary);
+        assert!(summary.contains("md=SHA512"), "{}", summary);
+
+        #[cfg(ossl110)]
+        assert_eq!(PkeyCtx::new(&pkey).unwrap().config_summary(), "");
     }
 
     #[test]
@@ -1000,6 +3173,354 @@ mod test {
         assert!(matches!(verifier.verify(&signature), Ok(true)));
     }
 
+    #[test]
+    fn rsa_sign_verify() {
+        let key = include_bytes!("../test/rsa.pem");
+        let rsa = Rsa::private_key_from_pem(key).unwrap();
+        let pkey = PKey::from_rsa(rsa).unwrap();
+
+        let mut ctx = PkeyCtx::new(&pkey).unwrap();
+        ctx.sign_init().unwrap();
+        ctx.set_rsa_padding(Padding::PKCS1).unwrap();
+        ctx.set_signature_md(Md::sha256()).unwrap();
+
+        let digest = hash(MessageDigest::sha256(), b"hello world").unwrap();
+        let len = ctx.sign(&digest, None).unwrap();
+        let mut signature = vec![];
+        assert_eq!(ctx.sign_to_vec(&digest, &mut signature).unwrap(), len);
+
+        ctx.verify_init().unwrap();
+        ctx.set_rsa_padding(Padding::PKCS1).unwrap();
+        ctx.set_signature_md(Md::sha256()).unwrap();
+        assert!(ctx.verify(&digest, &signature).unwrap());
+
+        signature[0] ^= 1;
+        assert!(!ctx.verify(&digest, &signature).unwrap());
+    }
+
+    #[test]
+    fn rsa_sign_md_nid() {
+        let key = include_bytes!("../test/rsa.pem");
+        let rsa = Rsa::private_key_from_pem(key).unwrap();
+        let pkey = PKey::from_rsa(rsa).unwrap();
+
+        let mut ctx = PkeyCtx::new(&pkey).unwrap();
+        ctx.sign_init().unwrap();
+        ctx.set_rsa_padding(Padding::PKCS1).unwrap();
+        ctx.set_signature_md_nid(Nid::SHA256).unwrap();
+        assert!(ctx.set_signature_md_nid(Nid::COMMONNAME).is_err());
+
+        let msg = b"hello world";
+        let digest = hash(MessageDigest::sha256(), msg).unwrap();
+        let mut signature = vec![];
+        ctx.sign_to_vec(&digest, &mut signature).unwrap();
+
+        let mut verifier = Verifier::new(MessageDigest::sha256(), &pkey).unwrap();
+        verifier.update(msg).unwrap();
+        assert!(matches!(verifier.verify(&signature), Ok(true)));
+    }
+
+    #[test]
+    #[cfg(all(feature = "no-rsa-pkcs1-encrypt", ossl110))]
+    fn rsa_pkcs1_encrypt_rejected() {
+        let key = include_bytes!("../test/rsa.pem");
+        let rsa = Rsa::private_key_from_pem(key).unwrap();
+        let pkey = PKey::from_rsa(rsa).unwrap();
+
+        let mut ctx = PkeyCtx::new(&pkey).unwrap();
+        ctx.encrypt_init().unwrap();
+        let e = ctx.set_rsa_padding(Padding::PKCS1).unwrap_err();
+        assert_eq!(
+            e.errors()[0].reason_code(),
+            ffi::RSA_R_ILLEGAL_OR_UNSUPPORTED_PADDING_MODE
+        );
+        ctx.set_rsa_padding(Padding::PKCS1_OAEP).unwrap();
+
+        ctx.decrypt_init().unwrap();
+        ctx.set_rsa_padding(Padding::PKCS1).unwrap_err();
+
+        ctx.sign_init().unwrap();
+        ctx.set_rsa_padding(Padding::PKCS1).unwrap();
+    }
+
+    #[test]
+    #[cfg(any(ossl110, boringssl, libressl360, awslc))]
+    fn ecies_round_trip() {
+        let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).unwrap();
+        let key = PKey::from_ec_key(EcKey::generate(&group).unwrap()).unwrap();
+        let public = PKey::public_key_from_der(&key.public_key_to_der().unwrap()).unwrap();
+        let msg = b"attack at dawn";
+
+        let sealed = ecies_seal(&public, msg, b"header").unwrap();
+        assert_eq!(sealed.ephemeral_public_key.len(), 65);
+        assert_eq!(sealed.ciphertext.len(), msg.len());
+        assert_eq!(ecies_open(&key, &sealed, b"header").unwrap(), msg);
+
+        let other = ecies_seal(&public, msg, b"header").unwrap();
+        assert_ne!(sealed.iv, other.iv);
+        assert_ne!(sealed.ephemeral_public_key, other.ephemeral_public_key);
+        assert_ne!(sealed.ciphertext, other.ciphertext);
+
+        ecies_open(&key, &sealed, b"other header").unwrap_err();
+        let mut tampered = sealed.clone();
+        tampered.ciphertext[0] ^= 1;
+        ecies_open(&key, &tampered, b"header").unwrap_err();
+        let wrong = PKey::from_ec_key(EcKey::generate(&group).unwrap()).unwrap();
+        ecies_open(&wrong, &sealed, b"header").unwrap_err();
+    }
+
+    #[test]
+    #[cfg(any(ossl110, boringssl, libressl360, awslc))]
+    fn derive_confirmed() {
+        let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).unwrap();
+        let alice = PKey::from_ec_key(EcKey::generate(&group).unwrap()).unwrap();
+        let bob = PKey::from_ec_key(EcKey::generate(&group).unwrap()).unwrap();
+        let transcript = b"alice's share || bob's share";
+
+        let a = super::derive_confirmed(&alice, &bob, transcript, true).unwrap();
+        let b = super::derive_confirmed(&bob, &alice, transcript, false).unwrap();
+        assert_eq!(a.key(), b.key());
+        assert_ne!(a.tag, b.tag);
+        assert!(a.verify_tag(&b.tag));
+        assert!(b.verify_tag(&a.tag));
+        // a reflected tag is rejected
+        assert!(!a.verify_tag(&a.tag));
+        assert!(!b.verify_tag(&b.tag));
+
+        let other = super::derive_confirmed(&bob, &alice, b"another transcript", false).unwrap();
+        assert_eq!(a.key(), other.key());
+        assert!(!a.verify_tag(&other.tag));
+        assert!(!a.verify_tag(&b.tag[..16]));
+
+        // both sides claiming the same role cannot confirm each other
+        let b = super::derive_confirmed(&bob, &alice, transcript, true).unwrap();
+        assert!(!a.verify_tag(&b.tag));
+    }
+
+    #[test]
+    fn rsa_raw_operations() {
+        let key = include_bytes!("../test/rsa.pem");
+        let rsa = Rsa::private_key_from_pem(key).unwrap();
+        let pkey = PKey::from_rsa(rsa).unwrap();
+        let size = pkey.size();
+        let msg = b"hello world";
+
+        // EME-PKCS1-v1_5: 0x00 || 0x02 || nonzero padding || 0x00 || message
+        let mut block = vec![0x00, 0x02];
+        block.resize(size - msg.len() - 1, 0xa5);
+        block.push(0x00);
+        block.extend_from_slice(msg);
+
+        let mut ctx = PkeyCtx::new(&pkey).unwrap();
+        let ct = ctx.raw_public_encrypt(&block).unwrap();
+        assert_eq!(ct.len(), size);
+        assert_eq!(ctx.raw_private_decrypt(&ct).unwrap(), block);
+
+        ctx.raw_public_encrypt(&block[1..]).unwrap_err();
+        let mut long = block.clone();
+        long.push(0);
+        ctx.raw_public_encrypt(&long).unwrap_err();
+    }
+
+    #[test]
+    #[cfg(not(feature = "no-rsa-pkcs1-encrypt"))]
+    fn rsa_raw_encrypt_pkcs1_decrypt() {
+        let key = include_bytes!("../test/rsa.pem");
+        let rsa = Rsa::private_key_from_pem(key).unwrap();
+        let pkey = PKey::from_rsa(rsa).unwrap();
+        let msg = b"hello world";
+
+        let mut block = vec![0x00, 0x02];
+        block.resize(pkey.size() - msg.len() - 1, 0xa5);
+        block.push(0x00);
+        block.extend_from_slice(msg);
+
+        let mut ctx = PkeyCtx::new(&pkey).unwrap();
+        let ct = ctx.raw_public_encrypt(&block).unwrap();
+        ctx.decrypt_init().unwrap();
+        ctx.set_rsa_padding(Padding::PKCS1).unwrap();
+        let mut pt = vec![];
+        ctx.decrypt_to_vec(&ct, &mut pt).unwrap();
+        assert_eq!(pt, msg);
+    }
+
+    #[test]
+    fn rsa_oaep_max_message_len() {
+        let rsa = Rsa::generate(2048).unwrap();
+        let pkey = PKey::from_rsa(rsa).unwrap();
+        let mut ctx = PkeyCtx::new(&pkey).unwrap();
+        ctx.encrypt_init().unwrap();
+        ctx.rsa_oaep_max_message_len().unwrap_err();
+
+        ctx.set_rsa_padding(Padding::PKCS1_OAEP).unwrap();
+        assert_eq!(ctx.rsa_oaep_max_message_len().unwrap(), 214);
+        ctx.set_rsa_oaep_md(Md::sha256()).unwrap();
+        let len = ctx.rsa_oaep_max_message_len().unwrap();
+        assert_eq!(len, 190);
+
+        let mut ct = vec![];
+        ctx.encrypt_to_vec(&vec![0; len], &mut ct).unwrap();
+        ctx.encrypt_to_vec(&vec![0; len + 1], &mut ct).unwrap_err();
+    }
+
+    #[test]
+    fn rsa_padding_mode() {
+        let key = include_bytes!("../test/rsa.pem");
+        let rsa = Rsa::private_key_from_pem(key).unwrap();
+        let pkey = PKey::from_rsa(rsa).unwrap();
+        let mut ctx = PkeyCtx::new(&pkey).unwrap();
+
+        ctx.encrypt_init().unwrap();
+        for (name, padding) in [("oaep", Padding::PKCS1_OAEP), ("none", Padding::NONE)] {
+            ctx.set_rsa_padding_mode(name).unwrap();
+            assert_eq!(ctx.rsa_padding().unwrap(), padding);
+        }
+
+        ctx.sign_init().unwrap();
+        for (name, padding) in [("pkcs1", Padding::PKCS1), ("pss", Padding::PKCS1_PSS)] {
+            ctx.set_rsa_padding_mode(name).unwrap();
+            assert_eq!(ctx.rsa_padding().unwrap(), padding);
+        }
+
+        let e = ctx.set_rsa_padding_mode("PSS").unwrap_err();
+        assert_eq!(e.errors()[0].reason_code(), ffi::RSA_R_UNKNOWN_PADDING_TYPE);
+        assert_eq!(ctx.rsa_padding().unwrap(), Padding::PKCS1_PSS);
+    }
+
+    #[test]
+    fn rsa_pss_saltlen() {
+        let key = include_bytes!("../test/rsa.pem");
+        let rsa = Rsa::private_key_from_pem(key).unwrap();
+        let pkey = PKey::from_rsa(rsa).unwrap();
+
+        let mut ctx = PkeyCtx::new(&pkey).unwrap();
+        ctx.sign_init().unwrap();
+        ctx.set_rsa_padding(Padding::PKCS1_PSS).unwrap();
+        ctx.set_signature_md(Md::sha256()).unwrap();
+
+        for saltlen in [
+            RsaPssSaltlen::DIGEST_LENGTH,
+            RsaPssSaltlen::MAXIMUM_LENGTH,
+            RsaPssSaltlen::MAXIMUM_LENGTH_STRICT,
+            RsaPssSaltlen::custom(20),
+        ] {
+            ctx.set_rsa_pss_saltlen(saltlen).unwrap();
+            assert_eq!(ctx.rsa_pss_saltlen().unwrap(), saltlen);
+        }
+    }
+
+    #[test]
+    fn rsa_pss_saltlen_sign_verify() {
+        let key = include_bytes!("../test/rsa.pem");
+        let rsa = Rsa::private_key_from_pem(key).unwrap();
+        let pkey = PKey::from_rsa(rsa).unwrap();
+        let digest = hash(MessageDigest::sha256(), b"hello world").unwrap();
+
+        let mut ctx = PkeyCtx::new(&pkey).unwrap();
+        ctx.sign_init().unwrap();
+        assert!(ctx
+            .set_rsa_pss_saltlen(RsaPssSaltlen::DIGEST_LENGTH)
+            .is_err());
+        ctx.set_rsa_padding(Padding::PKCS1_PSS).unwrap();
+        ctx.set_signature_md(Md::sha256()).unwrap();
+        ctx.set_rsa_pss_saltlen(RsaPssSaltlen::DIGEST_LENGTH)
+            .unwrap();
+        let mut signature = vec![];
+        ctx.sign_to_vec(&digest, &mut signature).unwrap();
+
+        ctx.verify_init().unwrap();
+        ctx.set_rsa_padding(Padding::PKCS1_PSS).unwrap();
+        ctx.set_signature_md(Md::sha256()).unwrap();
+        ctx.set_rsa_pss_saltlen(RsaPssSaltlen::custom(32)).unwrap();
+        assert!(ctx.verify(&digest, &signature).unwrap());
+
+        ctx.set_rsa_pss_saltlen(RsaPssSaltlen::custom(20)).unwrap();
+        assert!(!matches!(ctx.verify(&digest, &signature), Ok(true)));
+    }
+
+    #[test]
+    fn ecdsa_fixed() {
+        let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).unwrap();
+        let pkey = PKey::from_ec_key(EcKey::generate(&group).unwrap()).unwrap();
+        let digest = hash(MessageDigest::sha256(), b"COSE Sig_structure").unwrap();
+
+        let mut ctx = PkeyCtx::new(&pkey).unwrap();
+        ctx.sign_init().unwrap();
+        assert!(ctx.sign_ecdsa_fixed(&digest, 48).is_err());
+        let sig = ctx.sign_ecdsa_fixed(&digest, 32).unwrap();
+        assert_eq!(sig.len(), 64);
+
+        ctx.verify_init().unwrap();
+        assert!(ctx.verify_ecdsa_fixed(&digest, &sig, 32).unwrap());
+        assert!(!ctx.verify_ecdsa_fixed(&digest, &sig[..63], 32).unwrap());
+        assert!(ctx.verify_ecdsa_fixed(&digest, &sig, 66).is_err());
+    }
+
+    #[test]
+    fn ecdsa_jose() {
+        // RFC 7515 appendix A.3
+        let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).unwrap();
+        let x = "7fcdce2770f6c45d4183cbee6fdb4b7b580733357be9ef13bacf6e3c7bd15445";
+        let y = "c7f144cd1bbd9b7e872cdfedb9eeb9f4b3695d6ea90b24ad8a4623288588e5ad";
+        let x = BigNum::from_hex_str(x).unwrap();
+        let y = BigNum::from_hex_str(y).unwrap();
+        let ec_key = EcKey::from_public_key_affine_coordinates(&group, &x, &y).unwrap();
+        let pkey = PKey::from_ec_key(ec_key).unwrap();
+
+        let input = "eyJhbGciOiJFUzI1NiJ9.eyJpc3MiOiJqb2UiLA0KICJleHAiOjEzMDA4MTkzODAsDQogImh0dHA6Ly9leGFtcGxlLmNvbS9pc19yb290Ijp0cnVlfQ";
+        let tbs = hash(MessageDigest::sha256(), input.as_bytes()).unwrap();
+        let mut sig = hex::decode(
+            "0ed1215379636c483c2f7f155807d402a3b228033af97c7e17819ac3169ea665\
+             c50a07d38c3c70e5d8f12daf084a5480a66590c5f293509a8f3f7f8a83a354d5",
+        )
+        .unwrap();
+
+        let mut ctx = PkeyCtx::new(&pkey).unwrap();
+        ctx.verify_init().unwrap();
+        assert!(ctx.verify_ecdsa_jose(&tbs, &sig).unwrap());
+        assert!(!ctx.verify_ecdsa_jose(&tbs, &sig[1..]).unwrap());
+        sig[63] ^= 1;
+        assert!(!ctx.verify_ecdsa_jose(&tbs, &sig).unwrap());
+
+        let key = EcKey::generate(&group).unwrap();
+        let pkey = PKey::from_ec_key(key).unwrap();
+        let mut ctx = PkeyCtx::new(&pkey).unwrap();
+        for _ in 0..8 {
+            ctx.sign_init().unwrap();
+            let sig = ctx.sign_ecdsa_jose(&tbs).unwrap();
+            assert_eq!(sig.len(), 64);
+
+            ctx.verify_init().unwrap();
+            assert!(ctx.verify_ecdsa_jose(&tbs, &sig).unwrap());
+        }
+
+        let rsa = Rsa::generate(2048).unwrap();
+        let pkey = PKey::from_rsa(rsa).unwrap();
+        let mut ctx = PkeyCtx::new(&pkey).unwrap();
+        ctx.sign_init().unwrap();
+        ctx.sign_ecdsa_jose(&tbs).unwrap_err();
+    }
+
+    #[test]
+    fn rsa_sign_digestinfo() {
+        let key = include_bytes!("../test/rsa.pem");
+        let rsa = Rsa::private_key_from_pem(key).unwrap();
+        let pkey = PKey::from_rsa(rsa).unwrap();
+
+        let msg = b"hello world";
+        let mut digestinfo = hex::decode("3031300d060960864801650304020105000420").unwrap();
+        digestinfo.extend_from_slice(&hash(MessageDigest::sha256(), msg).unwrap());
+
+        let mut ctx = PkeyCtx::new(&pkey).unwrap();
+        ctx.sign_init().unwrap();
+        ctx.set_signature_md(Md::sha384()).unwrap();
+        let signature = ctx.sign_digestinfo(&digestinfo).unwrap();
+
+        let mut signer = Signer::new(MessageDigest::sha256(), &pkey).unwrap();
+        signer.update(msg).unwrap();
+        assert_eq!(signature, signer.sign_to_vec().unwrap());
+    }
+
     #[test]
     fn rsa_sign_pss() {
         let key = include_bytes!("../test/rsa.pem");
@@ -1022,24 +3543,75 @@ mod test {
         verifier
             .set_rsa_pss_saltlen(RsaPssSaltlen::custom(14))
             .unwrap();
-        verifier.update(msg).unwrap();
-        assert!(matches!(verifier.verify(&signature), Ok(true)));
+        verifier.update(msg).unwrap();
+        assert!(matches!(verifier.verify(&signature), Ok(true)));
+    }
+
+    #[test]
+    fn derive() {
+        let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).unwrap();
+        let key1 = EcKey::generate(&group).unwrap();
+        let key1 = PKey::from_ec_key(key1).unwrap();
+        let key2 = EcKey::generate(&group).unwrap();
+        let key2 = PKey::from_ec_key(key2).unwrap();
+
+        let mut ctx = PkeyCtx::new(&key1).unwrap();
+        ctx.derive_init().unwrap();
+        ctx.derive_set_peer(&key2).unwrap();
+
+        let mut buf = vec![];
+        ctx.derive_to_vec(&mut buf).unwrap();
+    }
+
+    #[test]
+    #[cfg(ossl111)]
+    fn derive_x448() {
+        let key1 = PKey::generate_x448().unwrap();
+        let key2 = PKey::generate_x448().unwrap();
+
+        let mut ctx = PkeyCtx::new(&key1).unwrap();
+        ctx.derive_init().unwrap();
+        ctx.derive_set_peer(&key2).unwrap();
+        let secret1 = ctx.derive_x448().unwrap();
+        assert!(ctx.derive_fixed::<32>().is_err());
+
+        let mut ctx = PkeyCtx::new(&key2).unwrap();
+        ctx.derive_init().unwrap();
+        ctx.derive_set_peer(&key1).unwrap();
+        let secret2 = ctx.derive_fixed::<56>().unwrap();
+        assert_eq!(secret1, secret2);
+
+        let x25519 = PKey::generate_x25519().unwrap();
+        let mut ctx = PkeyCtx::new(&x25519).unwrap();
+        ctx.derive_init().unwrap();
+        ctx.derive_set_peer(&PKey::generate_x25519().unwrap())
+            .unwrap();
+        assert!(ctx.derive_x448().is_err());
     }
 
     #[test]
-    fn derive() {
-        let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).unwrap();
-        let key1 = EcKey::generate(&group).unwrap();
-        let key1 = PKey::from_ec_key(key1).unwrap();
-        let key2 = EcKey::generate(&group).unwrap();
-        let key2 = PKey::from_ec_key(key2).unwrap();
+    #[cfg(ossl300)]
+    fn derive_dh_x942_kdf() {
+        let key1 = PKey::private_key_from_pem(include_bytes!("../test/dh_key1.pem")).unwrap();
+        let key2 = PKey::private_key_from_pem(include_bytes!("../test/dh_key2.pem")).unwrap();
+        let key2 = PKey::public_key_from_der(&key2.public_key_to_der().unwrap()).unwrap();
 
         let mut ctx = PkeyCtx::new(&key1).unwrap();
         ctx.derive_init().unwrap();
         ctx.derive_set_peer(&key2).unwrap();
+        ctx.set_dh_kdf_type(DhKdfType::X9_42).unwrap();
+        ctx.set_dh_kdf_md(Md::sha256()).unwrap();
+        ctx.set_dh_kdf_outlen(32).unwrap();
+        ctx.set_dh_kdf_ukm(&[1, 2, 3, 4, 5]).unwrap();
+        ctx.set_dh_kdf_oid(&Asn1Object::from_str("id-aes128-wrap").unwrap())
+            .unwrap();
 
-        let mut buf = vec![];
-        ctx.derive_to_vec(&mut buf).unwrap();
+        let mut buf = [0; 32];
+        ctx.derive(Some(&mut buf)).unwrap();
+        assert_eq!(
+            hex::encode(buf),
+            "732be842f3afa2d603ee26a0a3cc0b61c7826e826f623f1b10c6b78363cc41c4"
+        );
     }
 
     #[test]
@@ -1065,6 +3637,65 @@ mod test {
         assert_eq!(params.size(), 64);
     }
 
+    #[test]
+    #[cfg(ossl111)]
+    fn dh_pad() {
+        // the shared secret of these keys has a leading zero byte
+        let key1 = PKey::private_key_from_pem(include_bytes!("../test/dh_pad_key1.pem")).unwrap();
+        let key2 = PKey::private_key_from_pem(include_bytes!("../test/dh_pad_key2.pem")).unwrap();
+        let prime_len = key1.dh().unwrap().prime_p().num_bytes() as usize;
+
+        let derive = |key: &PKey<Private>, peer: &PKey<Private>, pad: bool| {
+            let mut ctx = PkeyCtx::new(key).unwrap();
+            ctx.derive_init().unwrap();
+            ctx.set_dh_pad(pad).unwrap();
+            ctx.derive_set_peer(peer).unwrap();
+            let mut secret = vec![];
+            ctx.derive_to_vec(&mut secret).unwrap();
+            secret
+        };
+
+        let padded = derive(&key1, &key2, true);
+        assert_eq!(padded.len(), prime_len);
+        assert_eq!(derive(&key2, &key1, true), padded);
+
+        let unpadded = derive(&key1, &key2, false);
+        assert!(unpadded.len() < prime_len);
+        assert!(padded.ends_with(&unpadded));
+    }
+
+    #[test]
+    #[cfg(not(any(libressl, boringssl, awslc)))]
+    fn dh_rfc5114() {
+        let mut ctx = PkeyCtx::new_id(Id::DHX).unwrap();
+        ctx.paramgen_init().unwrap();
+        ctx.set_dh_rfc5114(2).unwrap();
+        let params = ctx.paramgen().unwrap();
+
+        let keygen = || {
+            let mut ctx = PkeyCtx::new(&params).unwrap();
+            ctx.keygen_init().unwrap();
+            ctx.keygen().unwrap()
+        };
+        let key1 = keygen();
+        let key2 = keygen();
+        assert_eq!(key1.bits(), 2048);
+
+        let mut ctx = PkeyCtx::new(&key1).unwrap();
+        ctx.derive_init().unwrap();
+        ctx.derive_set_peer(&key2).unwrap();
+        let mut secret1 = vec![];
+        ctx.derive_to_vec(&mut secret1).unwrap();
+
+        let mut ctx = PkeyCtx::new(&key2).unwrap();
+        ctx.derive_init().unwrap();
+        ctx.derive_set_peer(&key1).unwrap();
+        let mut secret2 = vec![];
+        ctx.derive_to_vec(&mut secret2).unwrap();
+
+        assert_eq!(secret1, secret2);
+    }
+
     #[test]
     #[cfg(not(boringssl))]
     fn dsa_paramgen() {
@@ -1085,6 +3716,34 @@ mod test {
             }
         };
         assert_eq!(params.size(), size);
+
+        let mut ctx = PkeyCtx::new(&params).unwrap();
+        ctx.keygen_init().unwrap();
+        let key = ctx.keygen().unwrap();
+        assert_eq!(key.id(), Id::DSA);
+        let (dsa_params, dsa) = (params.dsa().unwrap(), key.dsa().unwrap());
+        assert_eq!(dsa.p(), dsa_params.p());
+        assert_eq!(dsa.q(), dsa_params.q());
+        assert_eq!(dsa.g(), dsa_params.g());
+    }
+
+    #[test]
+    #[cfg(not(boringssl))]
+    fn dsa_generate_with_params() {
+        let mut ctx = PkeyCtx::new_id(Id::DSA).unwrap();
+        ctx.paramgen_init().unwrap();
+        ctx.set_dsa_paramgen_bits(2048).unwrap();
+        let key = ctx.generate_with_params().unwrap();
+        assert_eq!(key.id(), Id::DSA);
+
+        let data = b"Some Crypto Text";
+        let mut signer = Signer::new(MessageDigest::sha256(), &key).unwrap();
+        signer.update(data).unwrap();
+        let signature = signer.sign_to_vec().unwrap();
+
+        let mut verifier = Verifier::new(MessageDigest::sha256(), &key).unwrap();
+        verifier.update(data).unwrap();
+        assert!(verifier.verify(&signature).unwrap());
     }
 
     #[test]
@@ -1098,6 +3757,75 @@ mod test {
         assert_eq!(params.size(), 72);
     }
 
+    #[test]
+    #[cfg(not(any(boringssl, awslc)))]
+    fn keygen_with_timeout() {
+        let mut ctx = PkeyCtx::new_id(Id::RSA).unwrap();
+        ctx.keygen_init().unwrap();
+        ctx.set_rsa_keygen_bits(8192).unwrap();
+        match ctx.keygen_with_timeout(Duration::from_nanos(1)) {
+            Err(KeygenError::Timeout) => {}
+            r => panic!("unexpected result: {:?}", r),
+        }
+
+        unsafe extern "C" fn count_cb(ctx: *mut ffi::EVP_PKEY_CTX) -> c_int {
+            *(ffi::EVP_PKEY_CTX_get_app_data(ctx) as *mut usize) += 1;
+            1
+        }
+
+        // a callback set by the caller is restored afterwards
+        let mut calls = 0usize;
+        unsafe {
+            ffi::EVP_PKEY_CTX_set_app_data(ctx.as_ptr(), &mut calls as *mut usize as *mut c_void);
+            ffi::EVP_PKEY_CTX_set_cb(ctx.as_ptr(), Some(count_cb));
+        }
+        ctx.set_rsa_keygen_bits(2048).unwrap();
+        let key = ctx.keygen_with_timeout(Duration::from_secs(600)).unwrap();
+        assert_eq!(key.bits(), 2048);
+        assert_eq!(calls, 0);
+
+        ctx.keygen().unwrap();
+        assert!(calls > 0);
+    }
+
+    #[test]
+    fn ec_keygen_p256() {
+        let mut ctx = PkeyCtx::new_id(Id::EC).unwrap();
+        ctx.keygen_init().unwrap();
+        ctx.set_ec_paramgen_curve_nid(Nid::X9_62_PRIME256V1)
+            .unwrap();
+        ctx.set_ec_param_enc(Asn1Flag::NAMED_CURVE).unwrap();
+        let key = ctx.keygen().unwrap();
+
+        assert_eq!(key.id(), Id::EC);
+        let ec_key = key.ec_key().unwrap();
+        assert_eq!(ec_key.group().curve_name(), Some(Nid::X9_62_PRIME256V1));
+        assert_eq!(ec_key.group().asn1_flag(), Asn1Flag::NAMED_CURVE);
+
+        // BoringSSL and AWS-LC only support named curves
+        #[cfg(not(any(boringssl, awslc)))]
+        {
+            ctx.set_ec_param_enc(Asn1Flag::EXPLICIT_CURVE).unwrap();
+            let key = ctx.keygen().unwrap();
+            let ec_key = key.ec_key().unwrap();
+            assert_eq!(ec_key.group().asn1_flag(), Asn1Flag::EXPLICIT_CURVE);
+        }
+    }
+
+    #[test]
+    fn keygen_public_only() {
+        let mut ctx = PkeyCtx::new_id(Id::EC).unwrap();
+        ctx.keygen_init().unwrap();
+        ctx.set_ec_paramgen_curve_nid(Nid::X9_62_PRIME256V1)
+            .unwrap();
+        let key = ctx.keygen_public_only().unwrap();
+
+        assert_eq!(key.id(), Id::EC);
+        let ec_key = key.ec_key().unwrap();
+        ec_key.check_key().unwrap();
+        assert!(unsafe { ffi::EC_KEY_get0_private_key(ec_key.as_ptr()) }.is_null());
+    }
+
     #[test]
     fn rsa_keygen() {
         let pubexp = BigNum::from_u32(65537).unwrap();
@@ -1105,9 +3833,70 @@ mod test {
         ctx.keygen_init().unwrap();
         ctx.set_rsa_keygen_pubexp(&pubexp).unwrap();
         ctx.set_rsa_keygen_bits(2048).unwrap();
+        // the context holds a copy of the exponent
+        drop(pubexp);
         let key = ctx.keygen().unwrap();
 
         assert_eq!(key.bits(), 2048);
+        let rsa = key.rsa().unwrap();
+        assert_eq!(rsa.size(), 256);
+        assert_eq!(rsa.e(), &*BigNum::from_u32(65537).unwrap());
+    }
+
+    #[test]
+    fn rsa_keygen_pubexp_u64() {
+        let mut ctx = PkeyCtx::new_id(Id::RSA).unwrap();
+        ctx.keygen_init().unwrap();
+
+        for e in [4, 1, 0] {
+            let err = ctx.set_rsa_keygen_pubexp_u64(e).unwrap_err();
+            assert_eq!(err.errors()[0].reason_code(), ffi::RSA_R_BAD_E_VALUE);
+        }
+
+        ctx.set_rsa_keygen_pubexp_u64(65537).unwrap();
+        ctx.set_rsa_keygen_bits(2048).unwrap();
+        let key = ctx.keygen().unwrap();
+        assert_eq!(key.rsa().unwrap().e(), &*BigNum::from_u32(65537).unwrap());
+    }
+
+    #[test]
+    fn rsa_keygen_pubexp_validation() {
+        let mut ctx = PkeyCtx::new_id(Id::RSA).unwrap();
+        ctx.keygen_init().unwrap();
+
+        for e in [65537, 3] {
+            ctx.set_rsa_keygen_pubexp(&BigNum::from_u32(e).unwrap())
+                .unwrap();
+        }
+        for e in [4, 1, 0] {
+            let err = ctx
+                .set_rsa_keygen_pubexp(&BigNum::from_u32(e).unwrap())
+                .unwrap_err();
+            assert_eq!(err.errors()[0].reason_code(), ffi::RSA_R_BAD_E_VALUE);
+        }
+        let mut negative = BigNum::from_u32(65537).unwrap();
+        negative.set_negative(true);
+        assert!(ctx.set_rsa_keygen_pubexp(&negative).is_err());
+
+        ctx.set_rsa_keygen_pubexp(&BigNum::from_u32(3).unwrap())
+            .unwrap();
+        ctx.set_rsa_keygen_bits(2048).unwrap();
+        let key = ctx.keygen().unwrap();
+        assert_eq!(key.rsa().unwrap().e(), &*BigNum::from_u32(3).unwrap());
+    }
+
+    #[test]
+    fn rsa_keygen_bits_floor() {
+        let mut ctx = PkeyCtx::new_id(Id::RSA).unwrap();
+        ctx.keygen_init().unwrap();
+
+        let err = ctx.set_rsa_keygen_bits(511).unwrap_err();
+        assert_eq!(err.errors()[0].reason_code(), ffi::RSA_R_KEY_SIZE_TOO_SMALL);
+        assert!(ctx.set_rsa_keygen_bits(0).is_err());
+
+        ctx.set_rsa_keygen_bits(512).unwrap();
+        let key = ctx.keygen().unwrap();
+        assert_eq!(key.bits(), 512);
     }
 
     #[test]
@@ -1132,6 +3921,117 @@ mod test {
         );
     }
 
+    #[test]
+    #[cfg(any(ossl111, libressl360))]
+    fn hkdf_rfc5869_derive_to_vec() {
+        let ikm = hex::decode("0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b").unwrap();
+        let salt = hex::decode("000102030405060708090a0b0c").unwrap();
+        let info = hex::decode("f0f1f2f3f4f5f6f7f8f9").unwrap();
+
+        let mut ctx = PkeyCtx::new_id(Id::HKDF).unwrap();
+        ctx.derive_init().unwrap();
+        ctx.set_hkdf_mode(HkdfMode::EXTRACT_ONLY).unwrap();
+        ctx.set_hkdf_md(Md::sha256()).unwrap();
+        ctx.set_hkdf_key(&ikm).unwrap();
+        ctx.set_hkdf_salt(&salt).unwrap();
+        let mut prk = vec![];
+        ctx.derive_to_vec(&mut prk).unwrap();
+        assert_eq!(
+            prk,
+            hex::decode("077709362c2e32df0ddc3f0dc47bba6390b6c73bb50f9c3122ec844ad7c2b3e5")
+                .unwrap()
+        );
+
+        let mut ctx = PkeyCtx::new_id(Id::HKDF).unwrap();
+        ctx.derive_init().unwrap();
+        ctx.set_hkdf_mode(HkdfMode::EXPAND_ONLY).unwrap();
+        ctx.set_hkdf_md(Md::sha256()).unwrap();
+        ctx.set_hkdf_key(&prk).unwrap();
+        ctx.add_hkdf_info(&info).unwrap();
+        // expand has no natural output length, so derive_to_vec must refuse rather than
+        // trying to allocate SIZE_MAX bytes
+        assert!(ctx.derive_to_vec(&mut vec![]).is_err());
+        let mut okm = [0; 42];
+        ctx.derive(Some(&mut okm)).unwrap();
+        assert_eq!(
+            &okm[..],
+            hex::decode("3cb25f25faacd57a90434f64d0362f2a2d2d0a90cf1a5a4c5db02d56ecc4c5bf34007208d5b887185865")
+                .unwrap()
+        );
+    }
+
+    #[test]
+    #[cfg(ossl110)]
+    fn tls1_prf() {
+        let derive = |seeds: &[&[u8]]| {
+            let mut ctx = PkeyCtx::new_id(Id::TLS1_PRF).unwrap();
+            ctx.derive_init().unwrap();
+            ctx.set_tls1_prf_md(Md::sha256()).unwrap();
+            ctx.set_tls1_prf_secret(&[0x0b; 48]).unwrap();
+            for seed in seeds {
+                ctx.add_tls1_prf_seed(seed).unwrap();
+            }
+            let mut out = [0; 48];
+            assert_eq!(ctx.derive(Some(&mut out)).unwrap(), 48);
+            out
+        };
+
+        let out = derive(&[b"master secret", &[0x01; 32], &[0x02; 32]]);
+        assert_ne!(out, [0; 48]);
+        assert_eq!(derive(&[b"master secret", &[0x01; 32], &[0x02; 32]]), out);
+        // seeds are concatenated
+        let mut seed = b"master secret".to_vec();
+        seed.extend_from_slice(&[0x01; 32]);
+        seed.extend_from_slice(&[0x02; 32]);
+        assert_eq!(derive(&[&seed]), out);
+        assert_ne!(derive(&[b"key expansion", &[0x01; 32], &[0x02; 32]]), out);
+    }
+
+    #[test]
+    #[cfg(ossl110)]
+    fn scrypt() {
+        let mut ctx = PkeyCtx::new_id(Id::SCRYPT).unwrap();
+        ctx.derive_init().unwrap();
+        ctx.set_scrypt_pass(b"password").unwrap();
+        ctx.set_scrypt_salt(b"NaCl").unwrap();
+        ctx.set_scrypt_n(1024).unwrap();
+        ctx.set_scrypt_r(8).unwrap();
+        ctx.set_scrypt_p(16).unwrap();
+        ctx.set_scrypt_maxmem_bytes(32 * 1024 * 1024).unwrap();
+
+        // scrypt has no natural output length
+        assert!(ctx.derive_to_vec(&mut vec![]).is_err());
+        let mut out = [0; 64];
+        ctx.derive(Some(&mut out)).unwrap();
+        assert_eq!(
+            &out[..],
+            hex::decode(
+                "fdbabe1c9d3472007856e7190d01e9fe7c6ad7cbc8237830e77376634b373162\
+                 2eaf30d92e22a3886ff109279d9830dac727afb94a83ee6d8360cbdfa2cc0640"
+            )
+            .unwrap()
+        );
+
+        ctx.set_scrypt_maxmem_bytes(1024).unwrap();
+        assert!(ctx.derive(Some(&mut out)).is_err());
+    }
+
+    #[test]
+    #[cfg(any(ossl110, boringssl, libressl360, awslc))]
+    fn hkdf_cipher_key() {
+        let mut ctx = PkeyCtx::new_id(Id::HKDF).unwrap();
+        ctx.derive_init().unwrap();
+        ctx.set_hkdf_md(Md::sha256()).unwrap();
+        ctx.set_hkdf_key(b"input keying material").unwrap();
+        ctx.add_hkdf_info(b"aes key").unwrap();
+        let key = ctx.derive_cipher_key(Cipher::aes_256_gcm()).unwrap();
+        assert_eq!(key.len(), 32);
+
+        let mut expected = [0; 32];
+        ctx.derive(Some(&mut expected)).unwrap();
+        assert_eq!(key, expected);
+    }
+
     #[test]
     #[cfg(any(ossl111, libressl360))]
     fn hkdf_expand() {
@@ -1156,6 +4056,85 @@ mod test {
         );
     }
 
+    #[test]
+    #[cfg(any(ossl111, libressl360))]
+    fn hkdf_expand_many_labels() {
+        let prk = hex::decode("077709362c2e32df0ddc3f0dc47bba6390b6c73bb50f9c3122ec844ad7c2b3e5")
+            .unwrap();
+        let keys =
+            hkdf_expand_many(&prk, Md::sha256(), &[(b"enc key", 16), (b"auth key", 32)]).unwrap();
+        assert_eq!(keys.len(), 2);
+        assert_eq!(keys[0].len(), 16);
+        assert_eq!(keys[1].len(), 32);
+
+        for (key, info) in keys.iter().zip([&b"enc key"[..], b"auth key"]) {
+            let mut ctx = PkeyCtx::new_id(Id::HKDF).unwrap();
+            ctx.derive_init().unwrap();
+            ctx.set_hkdf_mode(HkdfMode::EXPAND_ONLY).unwrap();
+            ctx.set_hkdf_md(Md::sha256()).unwrap();
+            ctx.set_hkdf_key(&prk).unwrap();
+            ctx.add_hkdf_info(info).unwrap();
+            let mut out = vec![0; key.len()];
+            ctx.derive(Some(&mut out)).unwrap();
+
+            assert_eq!(key, &out);
+        }
+    }
+
+    #[test]
+    fn derive_and_verify() {
+        let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).unwrap();
+        let key1 = PKey::from_ec_key(EcKey::generate(&group).unwrap()).unwrap();
+        let key2 = PKey::from_ec_key(EcKey::generate(&group).unwrap()).unwrap();
+
+        let mut ctx = PkeyCtx::new(&key2).unwrap();
+        ctx.derive_init().unwrap();
+        ctx.derive_set_peer(&key1).unwrap();
+        let mut expected = vec![];
+        ctx.derive_to_vec(&mut expected).unwrap();
+
+        let mut ctx = PkeyCtx::new(&key1).unwrap();
+        ctx.derive_init().unwrap();
+        ctx.derive_set_peer(&key2).unwrap();
+        assert!(ctx.derive_and_verify(&expected).unwrap());
+
+        let mut wrong = expected.clone();
+        *wrong.last_mut().unwrap() ^= 1;
+        assert!(!ctx.derive_and_verify(&wrong).unwrap());
+        assert!(!ctx
+            .derive_and_verify(&expected[..expected.len() - 1])
+            .unwrap());
+    }
+
+    #[test]
+    #[cfg(any(ossl111, boringssl, libressl370, awslc))]
+    fn x25519_raw_key_pair() {
+        let (private1, public1) = generate_x25519_raw().unwrap();
+        let (private2, public2) = generate_x25519_raw().unwrap();
+        assert_ne!(private1, private2);
+
+        let key1 = PKey::private_key_from_raw_bytes(&private1, Id::X25519).unwrap();
+        assert_eq!(key1.raw_public_key().unwrap(), public1);
+        let key2 = PKey::private_key_from_raw_bytes(&private2, Id::X25519).unwrap();
+        let peer1 = PKey::public_key_from_raw_bytes(&public1, Id::X25519).unwrap();
+        let peer2 = PKey::public_key_from_raw_bytes(&public2, Id::X25519).unwrap();
+
+        let mut ctx = PkeyCtx::new(&key1).unwrap();
+        ctx.derive_init().unwrap();
+        ctx.derive_set_peer(&peer2).unwrap();
+        let mut secret1 = vec![];
+        ctx.derive_to_vec(&mut secret1).unwrap();
+
+        let mut ctx = PkeyCtx::new(&key2).unwrap();
+        ctx.derive_init().unwrap();
+        ctx.derive_set_peer(&peer1).unwrap();
+        let mut secret2 = vec![];
+        ctx.derive_to_vec(&mut secret2).unwrap();
+
+        assert_eq!(secret1.len(), 32);
+        assert_eq!(secret1, secret2);
+    }
+
     #[test]
     #[cfg(any(ossl111, libressl360))]
     fn hkdf_extract() {
@@ -1177,6 +4156,33 @@ mod test {
         );
     }
 
+    #[test]
+    #[cfg(any(ossl111, libressl360))]
+    fn hkdf_extract_then_expand() {
+        // RFC 5869 Test Case 1
+        let ikm = hex::decode("0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b").unwrap();
+        let salt = hex::decode("000102030405060708090a0b0c").unwrap();
+        let prk = super::hkdf_extract(Md::sha256(), Some(&salt), &ikm).unwrap();
+        assert_eq!(
+            hex::encode(&prk),
+            "077709362c2e32df0ddc3f0dc47bba6390b6c73bb50f9c3122ec844ad7c2b3e5"
+        );
+
+        let info = hex::decode("f0f1f2f3f4f5f6f7f8f9").unwrap();
+        let okm = hkdf_expand_many(&prk, Md::sha256(), &[(&info, 42)]).unwrap();
+        assert_eq!(
+            hex::encode(&okm[0]),
+            "3cb25f25faacd57a90434f64d0362f2a2d2d0a90cf1a5a4c5db02d56ecc4c5bf34007208d5b887185865"
+        );
+
+        // RFC 5869 Test Case 3, which has no salt
+        let prk = super::hkdf_extract(Md::sha256(), None, &ikm).unwrap();
+        assert_eq!(
+            hex::encode(&prk),
+            "19ef24a32c717b167f33a91d6f648bdf96596776afdb6377ac434c1c293ccb04"
+        );
+    }
+
     #[test]
     fn verify_fail() {
         let key1 = Rsa::generate(4096).unwrap();
@@ -1197,6 +4203,30 @@ mod test {
         assert!(ErrorStack::get().errors().is_empty());
     }
 
+    #[test]
+    fn verify_signature_by_name() {
+        let rsa = PKey::from_rsa(Rsa::generate(2048).unwrap()).unwrap();
+        let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).unwrap();
+        let ec = PKey::from_ec_key(EcKey::generate(&group).unwrap()).unwrap();
+        let data = b"Some Crypto Text";
+
+        for (key, padding) in [(&rsa, Some(Padding::PKCS1)), (&ec, None)] {
+            let mut signer = Signer::new(MessageDigest::sha256(), key).unwrap();
+            signer.update(data).unwrap();
+            let mut signature = signer.sign_to_vec().unwrap();
+
+            assert!(verify_signature(key, "SHA256", padding, data, &signature).unwrap());
+            assert!(
+                !verify_signature(key, "SHA256", padding, b"Some Crypto text", &signature).unwrap()
+            );
+            let last = signature.len() - 1;
+            signature[last] ^= 1;
+            assert!(!verify_signature(key, "SHA256", padding, data, &signature).unwrap());
+            assert!(verify_signature(key, "not-a-digest", padding, data, &signature).is_err());
+        }
+        assert!(verify_signature(&ec, "SHA256", Some(Padding::PKCS1), data, &[]).is_err());
+    }
+
     #[test]
     fn verify_fail_ec() {
         let key1 =
@@ -1271,6 +4301,125 @@ mod test {
         assert!(ErrorStack::get().errors().is_empty());
     }
 
+    #[test]
+    #[cfg(ossl320)]
+    fn set_nonce_type_random_after_deterministic() {
+        let key1 =
+            EcKey::generate(&EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).unwrap()).unwrap();
+        let key1 = PKey::from_ec_key(key1).unwrap();
+        let hashed_input = hash(MessageDigest::sha256(), b"sample").unwrap();
+
+        let mut ctx = PkeyCtx::new(&key1).unwrap();
+        ctx.sign_init().unwrap();
+        ctx.set_signature_md(Md::sha256()).unwrap();
+        ctx.set_nonce_type(NonceType::DETERMINISTIC_K).unwrap();
+        let mut sig1 = vec![];
+        ctx.sign_to_vec(&hashed_input, &mut sig1).unwrap();
+        let mut sig2 = vec![];
+        ctx.sign_to_vec(&hashed_input, &mut sig2).unwrap();
+        assert_eq!(sig1, sig2);
+
+        ctx.set_nonce_type(NonceType::RANDOM_K).unwrap();
+        assert_eq!(ctx.nonce_type().unwrap(), NonceType::RANDOM_K);
+        let mut sig1 = vec![];
+        ctx.sign_to_vec(&hashed_input, &mut sig1).unwrap();
+        let mut sig2 = vec![];
+        ctx.sign_to_vec(&hashed_input, &mut sig2).unwrap();
+        assert_ne!(sig1, sig2);
+    }
+
+    #[test]
+    fn configure_signature() {
+        let rsa = PKey::from_rsa(Rsa::generate(2048).unwrap()).unwrap();
+        let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).unwrap();
+        let ec = PKey::from_ec_key(EcKey::generate(&group).unwrap()).unwrap();
+        let digest = hash(MessageDigest::sha256(), b"hello world").unwrap();
+
+        let schemes = [
+            (&rsa, SignatureScheme::RsaPkcs1Sha256),
+            (&rsa, SignatureScheme::RsaPssSha256),
+            (&ec, SignatureScheme::EcdsaSha256),
+        ];
+        for (key, scheme) in schemes {
+            let mut ctx = PkeyCtx::new(key).unwrap();
+            ctx.sign_init().unwrap();
+            ctx.configure_signature(scheme).unwrap();
+            let mut signature = vec![];
+            ctx.sign_to_vec(&digest, &mut signature).unwrap();
+
+            for (other_key, other) in schemes {
+                let mut ctx = PkeyCtx::new(other_key).unwrap();
+                ctx.verify_init().unwrap();
+                let valid = ctx.configure_signature(other).is_ok()
+                    && ctx.verify(&digest, &signature).unwrap_or(false);
+                assert_eq!(valid, scheme == other, "{:?} {:?}", scheme, other);
+            }
+        }
+
+        let mut ctx = PkeyCtx::new(&rsa).unwrap();
+        ctx.sign_init().unwrap();
+        ctx.configure_signature(SignatureScheme::EcdsaSha256)
+            .unwrap_err();
+    }
+
+    #[test]
+    #[cfg(ossl111)]
+    fn configure_signature_ed25519() {
+        use crate::md_ctx::MdCtx;
+
+        let key = PKey::generate_ed25519().unwrap();
+        let msg = b"hello world";
+
+        let mut ctx = MdCtx::new().unwrap();
+        ctx.digest_sign_init(None, &key)
+            .unwrap()
+            .configure_signature(SignatureScheme::Ed25519)
+            .unwrap();
+        let mut signature = vec![];
+        ctx.digest_sign_to_vec(msg, &mut signature).unwrap();
+
+        let mut ctx = MdCtx::new().unwrap();
+        ctx.digest_verify_init(None, &key)
+            .unwrap()
+            .configure_signature(SignatureScheme::Ed25519)
+            .unwrap();
+        assert!(ctx.digest_verify(msg, &signature).unwrap());
+
+        let rsa = PKey::from_rsa(Rsa::generate(2048).unwrap()).unwrap();
+        let mut ctx = PkeyCtx::new(&rsa).unwrap();
+        ctx.verify_init().unwrap();
+        ctx.configure_signature(SignatureScheme::Ed25519)
+            .unwrap_err();
+    }
+
+    #[test]
+    #[cfg(ossl320)]
+    fn ed25519ph_sign_verify() {
+        use crate::md_ctx::MdCtx;
+
+        let key = PKey::generate_ed25519().unwrap();
+        let msg = vec![0x5a; 1 << 16];
+
+        let mut ctx = MdCtx::new().unwrap();
+        ctx.digest_sign_init(None, &key)
+            .unwrap()
+            .set_eddsa_instance(EddsaInstance::ED25519PH)
+            .unwrap();
+        let mut signature = vec![];
+        ctx.digest_sign_to_vec(&msg, &mut signature).unwrap();
+
+        let mut ctx = MdCtx::new().unwrap();
+        ctx.digest_verify_init(None, &key)
+            .unwrap()
+            .set_eddsa_instance(EddsaInstance::ED25519PH)
+            .unwrap();
+        assert!(ctx.digest_verify(&msg, &signature).unwrap());
+
+        let mut ctx = MdCtx::new().unwrap();
+        ctx.digest_verify_init(None, &key).unwrap();
+        assert!(!ctx.digest_verify(&msg, &signature).unwrap_or(false));
+    }
+
     // Test vector from
     // https://github.com/openssl/openssl/blob/openssl-3.2.0/test/recipes/30-test_evp_data/evppkey_ecdsa_rfc6979.txt
     #[test]
@@ -1297,4 +4446,19 @@ mxJ7imIrEg9nIQ==
         assert_eq!(output, expected_output);
         assert!(ErrorStack::get().errors().is_empty());
     }
+
+    #[test]
+    #[cfg(ossl110)]
+    fn operation_flags() {
+        let key =
+            EcKey::generate(&EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).unwrap()).unwrap();
+        let key = PKey::from_ec_key(key).unwrap();
+
+        let mut ctx = PkeyCtx::new(&key).unwrap();
+        assert_eq!(ctx.operation_flags(), 0);
+        ctx.derive_init().unwrap();
+        assert_eq!(ctx.operation_flags(), ffi::EVP_PKEY_OP_DERIVE);
+        ctx.sign_init().unwrap();
+        assert_eq!(ctx.operation_flags(), ffi::EVP_PKEY_OP_SIGN);
+    }
 }
@@ -90,6 +90,7 @@ cfg_if! {
 }
 
 /// Salt lengths that must be used with `set_rsa_pss_saltlen`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct RsaPssSaltlen(c_int);
 
 impl RsaPssSaltlen {
@@ -108,7 +109,13 @@ impl RsaPssSaltlen {
     pub const DIGEST_LENGTH: RsaPssSaltlen = RsaPssSaltlen(-1);
     /// The salt length is set to the maximum permissible value.
     /// Corresponds to the special value `-2`.
+    ///
+    /// When verifying, this value instead detects the salt length from the signature.
     pub const MAXIMUM_LENGTH: RsaPssSaltlen = RsaPssSaltlen(-2);
+    /// The salt length is set to the maximum permissible value, both when signing and when
+    /// verifying.
+    /// Corresponds to the special value `-3`.
+    pub const MAXIMUM_LENGTH_STRICT: RsaPssSaltlen = RsaPssSaltlen(-3);
 }
 
 /// A type which computes cryptographic signatures of data.
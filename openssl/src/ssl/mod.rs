@@ -1823,6 +1823,14 @@ impl SslContext {
 }
 
 impl SslContextRef {
+    /// Returns a reference to the X509 verification configuration.
+    ///
+    /// Requires AWS-LC or BoringSSL or LibreSSL or OpenSSL 1.0.2 or newer.
+    #[corresponds(SSL_CTX_get0_param)]
+    pub fn verify_param(&self) -> &X509VerifyParamRef {
+        unsafe { X509VerifyParamRef::from_ptr(ffi::SSL_CTX_get0_param(self.as_ptr())) }
+    }
+
     /// Returns the certificate associated with this `SslContext`, if present.
     ///
     /// Requires LibreSSL or OpenSSL 1.1.0 or newer.
@@ -2728,6 +2736,14 @@ impl SslRef {
         }
     }
 
+    /// Returns a reference to the X509 verification configuration.
+    ///
+    /// Requires AWS-LC or BoringSSL or LibreSSL or OpenSSL 1.0.2 or newer.
+    #[corresponds(SSL_get0_param)]
+    pub fn param(&self) -> &X509VerifyParamRef {
+        unsafe { X509VerifyParamRef::from_ptr(ffi::SSL_get0_param(self.as_ptr())) }
+    }
+
     /// Returns a mutable reference to the X509 verification configuration.
     ///
     /// Requires AWS-LC or BoringSSL or LibreSSL or OpenSSL 1.0.2 or newer.
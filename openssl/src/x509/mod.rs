@@ -205,6 +205,16 @@ impl X509StoreContextRef {
             }
         }
     }
+
+    /// Returns a mutable reference to the verification parameters of the context.
+    ///
+    /// This will only work inside of a call to `init`.
+    #[corresponds(X509_STORE_CTX_get0_param)]
+    pub fn verify_param_mut(&mut self) -> &mut verify::X509VerifyParamRef {
+        unsafe {
+            verify::X509VerifyParamRef::from_ptr_mut(ffi::X509_STORE_CTX_get0_param(self.as_ptr()))
+        }
+    }
 }
 
 /// A builder used to construct an `X509`.
@@ -445,6 +455,20 @@ impl X509Ref {
         }
     }
 
+    /// Returns the OIDs in this certificate's extended key usage extension, if it exists.
+    #[corresponds(X509_get_ext_d2i)]
+    pub fn extended_key_usage(&self) -> Option<Stack<Asn1Object>> {
+        unsafe {
+            let stack = ffi::X509_get_ext_d2i(
+                self.as_ptr(),
+                ffi::NID_ext_key_usage,
+                ptr::null_mut(),
+                ptr::null_mut(),
+            );
+            Stack::from_ptr_opt(stack as *mut _)
+        }
+    }
+
     /// Returns this certificate's issuer alternative name entries, if they exist.
     #[corresponds(X509_get_ext_d2i)]
     pub fn issuer_alt_names(&self) -> Option<Stack<GeneralName>> {
@@ -603,6 +627,24 @@ impl X509Ref {
         unsafe { cvt_p(ffi::X509_get1_ocsp(self.as_ptr())).map(|p| Stack::from_ptr(p)) }
     }
 
+    /// Attaches the default SM2 distinguishing ID, `1234567812345678` from GM/T 0009-2012, to
+    /// this certificate.
+    ///
+    /// The ID is not part of the encoded certificate, but OpenSSL uses the ID attached to the
+    /// certificate object when verifying its SM2 signature. Without an attached ID, OpenSSL
+    /// verifies with an empty ID, which does not match certificates signed by issuers following
+    /// GM/T 0009-2012. This replaces any ID attached previously.
+    ///
+    /// Requires OpenSSL 3.0.0 or newer built with SM2 support.
+    #[corresponds(X509_set0_distinguishing_id)]
+    #[cfg(all(ossl300, not(osslconf = "OPENSSL_NO_SM2")))]
+    pub fn set_sm2_default_id(&mut self) -> Result<(), ErrorStack> {
+        let id = crate::asn1::Asn1OctetString::new_from_bytes(b"1234567812345678")?;
+        unsafe { ffi::X509_set0_distinguishing_id(self.as_ptr(), id.as_ptr()) };
+        mem::forget(id);
+        Ok(())
+    }
+
     /// Checks that this certificate issued `subject`.
     #[corresponds(X509_check_issued)]
     pub fn issued(&self, subject: &X509Ref) -> X509VerifyResult {
@@ -1731,6 +1773,39 @@ foreign_type_and_impl_send_sync! {
     pub struct X509CrlRef;
 }
 
+impl Stackable for X509Crl {
+    type StackType = ffi::stack_st_X509_CRL;
+}
+
+impl ToOwned for X509CrlRef {
+    type Owned = X509Crl;
+
+    fn to_owned(&self) -> X509Crl {
+        unsafe {
+            ffi::X509_CRL_up_ref(self.as_ptr());
+            X509Crl::from_ptr(self.as_ptr())
+        }
+    }
+}
+
+impl Clone for X509Crl {
+    fn clone(&self) -> X509Crl {
+        X509CrlRef::to_owned(self)
+    }
+}
+
+impl fmt::Debug for X509Crl {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut debug_struct = formatter.debug_struct("X509Crl");
+        debug_struct.field("issuer", &self.issuer_name());
+        debug_struct.field("last_update", &self.last_update());
+        if let Some(next_update) = self.next_update() {
+            debug_struct.field("next_update", &next_update);
+        }
+        debug_struct.finish()
+    }
+}
+
 /// The status of a certificate in a revoction list
 ///
 /// Corresponds to the return value from the [`X509_CRL_get0_by_*`] methods.
@@ -1,13 +1,19 @@
 use std::cmp::Ordering;
+use std::io;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use crate::asn1::{Asn1Object, Asn1OctetString, Asn1Time};
 use crate::bn::{BigNum, MsbOption};
-use crate::hash::MessageDigest;
+use crate::ec::{EcGroup, EcKey};
+use crate::hash::{hash, MessageDigest};
 use crate::nid::Nid;
-use crate::pkey::{PKey, Private};
+#[cfg(not(osslconf = "OPENSSL_NO_OCSP"))]
+use crate::ocsp::{OcspCertStatus, OcspResponse, OcspRevokedStatus};
+use crate::pkey::{PKey, PKeyRef, Private};
 use crate::rsa::Rsa;
 #[cfg(not(any(boringssl, awslc)))]
 use crate::ssl::SslFiletype;
+use crate::ssl::{Ssl, SslContext, SslMethod};
 use crate::stack::Stack;
 use crate::x509::extension::{
     AuthorityKeyIdentifier, BasicConstraints, ExtendedKeyUsage, KeyUsage, SubjectAlternativeName,
@@ -15,15 +21,20 @@ use crate::x509::extension::{
 };
 #[cfg(not(any(boringssl, awslc)))]
 use crate::x509::store::X509Lookup;
-use crate::x509::store::X509StoreBuilder;
-use crate::x509::verify::{X509VerifyFlags, X509VerifyParam};
+use crate::x509::store::{X509Store, X509StoreBuilder};
+use crate::x509::verify::{
+    check_host_rust, verify_bundle, verify_chain, verify_chain_report, verify_chain_with_pins,
+    verify_client_cert, verify_code_signing, verify_tsa_chain, ChainError, ChainPolicy,
+    ChainTermination, VerifyError, X509CheckFlags, X509KeyUsage, X509VerifyFlags, X509VerifyParam,
+};
+#[cfg(ossl110)]
+use crate::x509::CrlReason;
 #[cfg(any(ossl110, boringssl, awslc))]
 use crate::x509::X509PurposeId;
 use crate::x509::X509PurposeRef;
-#[cfg(ossl110)]
-use crate::x509::{CrlReason, X509Builder};
 use crate::x509::{
-    CrlStatus, X509Crl, X509Extension, X509Name, X509Req, X509StoreContext, X509VerifyResult, X509,
+    CrlStatus, X509Builder, X509Crl, X509Extension, X509Name, X509Ref, X509Req, X509StoreContext,
+    X509VerifyResult, X509,
 };
 #[cfg(ossl110)]
 use foreign_types::ForeignType;
@@ -37,6 +48,85 @@ fn pkey() -> PKey<Private> {
     PKey::from_rsa(rsa).unwrap()
 }
 
+fn ec_pkey() -> PKey<Private> {
+    let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).unwrap();
+    PKey::from_ec_key(EcKey::generate(&group).unwrap()).unwrap()
+}
+
+/// Issues a certificate named `cn` for `key`, signed by `issuer` or self-signed if `None`.
+///
+/// The certificate is valid for a year from now. `customize` is called just before signing.
+fn issue_cert(
+    cn: &str,
+    key: &PKeyRef<Private>,
+    issuer: Option<(&X509Ref, &PKeyRef<Private>)>,
+    ca: bool,
+    customize: impl FnOnce(&mut X509Builder),
+) -> X509 {
+    issue_cert_with_digest(cn, key, issuer, ca, MessageDigest::sha256(), customize)
+}
+
+/// Like `issue_cert`, but signs the certificate using `digest`.
+fn issue_cert_with_digest(
+    cn: &str,
+    key: &PKeyRef<Private>,
+    issuer: Option<(&X509Ref, &PKeyRef<Private>)>,
+    ca: bool,
+    digest: MessageDigest,
+    customize: impl FnOnce(&mut X509Builder),
+) -> X509 {
+    let mut name = X509Name::builder().unwrap();
+    name.append_entry_by_nid(Nid::COMMONNAME, cn).unwrap();
+    let name = name.build();
+
+    let mut builder = X509::builder().unwrap();
+    builder.set_version(2).unwrap();
+    let mut serial = BigNum::new().unwrap();
+    serial.rand(64, MsbOption::MAYBE_ZERO, false).unwrap();
+    builder
+        .set_serial_number(&serial.to_asn1_integer().unwrap())
+        .unwrap();
+    builder.set_subject_name(&name).unwrap();
+    builder
+        .set_issuer_name(issuer.map_or(&name, |(cert, _)| cert.subject_name()))
+        .unwrap();
+    builder.set_pubkey(key).unwrap();
+    builder
+        .set_not_before(&Asn1Time::days_from_now(0).unwrap())
+        .unwrap();
+    builder
+        .set_not_after(&Asn1Time::days_from_now(365).unwrap())
+        .unwrap();
+    if ca {
+        builder
+            .append_extension(BasicConstraints::new().critical().ca().build().unwrap())
+            .unwrap();
+        builder
+            .append_extension(
+                KeyUsage::new()
+                    .critical()
+                    .key_cert_sign()
+                    .crl_sign()
+                    .build()
+                    .unwrap(),
+            )
+            .unwrap();
+    }
+    customize(&mut builder);
+    builder
+        .sign(issuer.map_or(key, |(_, key)| key), digest)
+        .unwrap();
+    builder.build()
+}
+
+fn store_with(roots: &[&X509]) -> X509Store {
+    let mut builder = X509StoreBuilder::new().unwrap();
+    for root in roots {
+        builder.add_cert((*root).clone()).unwrap();
+    }
+    builder.build()
+}
+
 #[test]
 fn test_cert_loading() {
     let cert = include_bytes!("../../test/cert.pem");
@@ -890,6 +980,55 @@ fn test_verify_param_set_time() {
         .unwrap());
 }
 
+#[test]
+fn test_verify_param_set_system_time() {
+    let valid_in_2010 = |b: &mut X509Builder| {
+        // 2010-01-01 to 2011-01-01
+        b.set_not_before(&Asn1Time::from_unix(1262304000).unwrap())
+            .unwrap();
+        b.set_not_after(&Asn1Time::from_unix(1293840000).unwrap())
+            .unwrap();
+    };
+    let root_key = pkey();
+    let root = issue_cert("root", &root_key, None, true, valid_in_2010);
+    let leaf = issue_cert(
+        "leaf",
+        &pkey(),
+        Some((&root, &root_key)),
+        false,
+        valid_in_2010,
+    );
+    let chain = Stack::new().unwrap();
+    let store = store_with(&[&root]);
+
+    let param = X509VerifyParam::new().unwrap();
+    match verify_chain(&leaf, &chain, &store, &param, &ChainPolicy::new()) {
+        Err(ChainError::Verify {
+            error: VerifyError::Expired,
+            ..
+        }) => {}
+        r => panic!("unexpected result: {:?}", r.map(|_| ())),
+    }
+
+    let mut param = X509VerifyParam::new().unwrap();
+    // 2010-06-01
+    param
+        .set_system_time(UNIX_EPOCH + Duration::from_secs(1275350400))
+        .unwrap();
+    verify_chain(&leaf, &chain, &store, &param, &ChainPolicy::new()).unwrap();
+
+    param
+        .set_system_time(UNIX_EPOCH - Duration::from_secs(86400))
+        .unwrap();
+    match verify_chain(&leaf, &chain, &store, &param, &ChainPolicy::new()) {
+        Err(ChainError::Verify {
+            error: VerifyError::NotYetValid,
+            ..
+        }) => {}
+        r => panic!("unexpected result: {:?}", r.map(|_| ())),
+    }
+}
+
 #[test]
 fn test_verify_param_set_depth() {
     let cert = include_bytes!("../../test/leaf.pem");
@@ -981,6 +1120,15 @@ fn test_load_cert_file() {
         .unwrap());
 }
 
+#[test]
+fn test_verify_param_depth() {
+    let mut param = X509VerifyParam::new().unwrap();
+    assert_eq!(param.depth(), -1);
+
+    param.set_depth(2);
+    assert_eq!(param.depth(), 2);
+}
+
 #[test]
 #[cfg(ossl110)]
 fn test_verify_param_auth_level() {
@@ -1251,3 +1399,1349 @@ fn test_store_all_certificates() {
 
     assert_eq!(store.all_certificates().len(), 1);
 }
+
+#[test]
+fn test_verify_chain_min_rsa_bits() {
+    let root_key = pkey();
+    let root = issue_cert("root", &root_key, None, true, |_| {});
+    let intermediate_key = PKey::from_rsa(Rsa::generate(1024).unwrap()).unwrap();
+    let intermediate = issue_cert(
+        "intermediate",
+        &intermediate_key,
+        Some((&root, &root_key)),
+        true,
+        |_| {},
+    );
+    let leaf = issue_cert(
+        "leaf",
+        &ec_pkey(),
+        Some((&intermediate, &intermediate_key)),
+        false,
+        |_| {},
+    );
+    let mut chain = Stack::new().unwrap();
+    chain.push(intermediate).unwrap();
+    let store = store_with(&[&root]);
+    let param = X509VerifyParam::new().unwrap();
+
+    let verified = verify_chain(&leaf, &chain, &store, &param, &ChainPolicy::new()).unwrap();
+    assert_eq!(verified.chain().len(), 3);
+
+    let mut policy = ChainPolicy::new();
+    policy.set_min_rsa_bits(2048);
+    match verify_chain(&leaf, &chain, &store, &param, &policy) {
+        Err(ChainError::RsaKeyTooSmall { depth, bits }) => {
+            assert_eq!(depth, 1);
+            assert_eq!(bits, 1024);
+        }
+        r => panic!("unexpected result: {:?}", r.map(|_| ())),
+    }
+}
+
+#[test]
+fn test_verify_chain_required_eku() {
+    let root_key = pkey();
+    let root = issue_cert("root", &root_key, None, true, |_| {});
+    let with_eku = issue_cert("leaf", &ec_pkey(), Some((&root, &root_key)), false, |b| {
+        let eku = ExtendedKeyUsage::new()
+            .client_auth()
+            .other("1.3.6.1.4.1.55555.1")
+            .build()
+            .unwrap();
+        b.append_extension(eku).unwrap();
+    });
+    let without_eku = issue_cert("leaf", &ec_pkey(), Some((&root, &root_key)), false, |b| {
+        let eku = ExtendedKeyUsage::new().client_auth().build().unwrap();
+        b.append_extension(eku).unwrap();
+    });
+    let chain = Stack::new().unwrap();
+    let store = store_with(&[&root]);
+    let param = X509VerifyParam::new().unwrap();
+
+    let oid = Asn1Object::from_str("1.3.6.1.4.1.55555.1").unwrap();
+    let mut policy = ChainPolicy::new();
+    policy.set_required_eku(&oid);
+
+    verify_chain(&with_eku, &chain, &store, &param, &policy).unwrap();
+    match verify_chain(&without_eku, &chain, &store, &param, &policy) {
+        Err(ChainError::MissingEku(missing)) => assert_eq!(missing, oid),
+        r => panic!("unexpected result: {:?}", r.map(|_| ())),
+    }
+}
+
+#[test]
+fn test_verify_flags_bits() {
+    assert_eq!(
+        X509VerifyFlags::IGNORE_CRITICAL.bits(),
+        ffi::X509_V_FLAG_IGNORE_CRITICAL
+    );
+    assert_eq!(
+        X509VerifyFlags::X509_STRICT.bits(),
+        ffi::X509_V_FLAG_X509_STRICT
+    );
+    #[cfg(ossl110)]
+    {
+        assert_eq!(
+            X509VerifyFlags::SUITEB_192_LOS.bits(),
+            ffi::X509_V_FLAG_SUITEB_192_LOS
+        );
+        assert_eq!(
+            X509VerifyFlags::SUITEB_128_LOS.bits(),
+            ffi::X509_V_FLAG_SUITEB_128_LOS
+        );
+    }
+}
+
+#[test]
+fn test_verify_chain_error_kinds() {
+    let root_key = pkey();
+    let root = issue_cert("root", &root_key, None, true, |_| {});
+    let leaf = issue_cert("leaf", &ec_pkey(), Some((&root, &root_key)), false, |b| {
+        let san = SubjectAlternativeName::new()
+            .dns("example.com")
+            .build(&b.x509v3_context(Some(&root), None))
+            .unwrap();
+        b.append_extension(san).unwrap();
+    });
+    let chain = Stack::new().unwrap();
+    let store = store_with(&[&root]);
+    let policy = ChainPolicy::new();
+
+    let verify = |store: &X509Store, param: &X509VerifyParam| match verify_chain(
+        &leaf, &chain, store, param, &policy,
+    ) {
+        Err(ChainError::Verify { error, .. }) => error,
+        r => panic!("unexpected result: {:?}", r.map(|_| ())),
+    };
+
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+    let mut param = X509VerifyParam::new().unwrap();
+    param.set_time((now.as_secs() + 400 * 24 * 60 * 60) as time_t);
+    assert_eq!(verify(&store, &param), VerifyError::Expired);
+    let err = verify_chain(&leaf, &chain, &store, &param, &policy)
+        .err()
+        .unwrap();
+    assert!(err.to_string().contains("expired"));
+
+    let mut param = X509VerifyParam::new().unwrap();
+    param.set_time(0);
+    assert_eq!(verify(&store, &param), VerifyError::NotYetValid);
+
+    let mut param = X509VerifyParam::new().unwrap();
+    param.set_host("example.org").unwrap();
+    assert_eq!(verify(&store, &param), VerifyError::HostnameMismatch);
+
+    let param = X509VerifyParam::new().unwrap();
+    assert_eq!(
+        verify(&store_with(&[]), &param),
+        VerifyError::UnableToGetIssuerLocally
+    );
+
+    let err = VerifyError::from_raw(ffi::X509_V_ERR_CERT_HAS_EXPIRED);
+    assert_eq!(err, VerifyError::Expired);
+    assert_eq!(err.to_string(), "certificate has expired");
+    assert_eq!(VerifyError::from_raw(1000), VerifyError::Other(1000));
+    assert_eq!(VerifyError::Other(1000).as_raw(), 1000);
+    assert!(VerifyError::Other(1000)
+        .to_string()
+        .ends_with("(code 1000)"));
+
+    let err = io::Error::from(VerifyError::HostnameMismatch);
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    assert_eq!(err.to_string(), "hostname mismatch");
+}
+
+#[test]
+fn test_verify_chain_reject_sha1_signatures() {
+    let root_key = pkey();
+    let root = issue_cert("root", &root_key, None, true, |_| {});
+    let ca_key = pkey();
+    let sha1_ca = issue_cert_with_digest(
+        "ca",
+        &ca_key,
+        Some((&root, &root_key)),
+        true,
+        MessageDigest::sha1(),
+        |_| {},
+    );
+    let sha256_ca = issue_cert("ca", &ca_key, Some((&root, &root_key)), true, |_| {});
+    let leaf = issue_cert(
+        "leaf",
+        &ec_pkey(),
+        Some((&sha256_ca, &ca_key)),
+        false,
+        |_| {},
+    );
+    let store = store_with(&[&root]);
+    let param = X509VerifyParam::new().unwrap();
+
+    let mut policy = ChainPolicy::new();
+    policy.set_reject_sha1_signatures(true);
+
+    let mut chain = Stack::new().unwrap();
+    chain.push(sha256_ca).unwrap();
+    verify_chain(&leaf, &chain, &store, &param, &policy).unwrap();
+
+    let mut chain = Stack::new().unwrap();
+    chain.push(sha1_ca).unwrap();
+    verify_chain(&leaf, &chain, &store, &param, &ChainPolicy::new()).unwrap();
+    match verify_chain(&leaf, &chain, &store, &param, &policy) {
+        Err(ChainError::Sha1Signature { depth }) => assert_eq!(depth, 1),
+        r => panic!("unexpected result: {:?}", r.map(|_| ())),
+    }
+}
+
+#[test]
+fn test_verify_chain_allowed_signature_algorithms() {
+    let root_key = pkey();
+    let root = issue_cert("root", &root_key, None, true, |_| {});
+    let ca_key = pkey();
+    let sha1_ca = issue_cert_with_digest(
+        "ca",
+        &ca_key,
+        Some((&root, &root_key)),
+        true,
+        MessageDigest::sha1(),
+        |_| {},
+    );
+    let sha256_ca = issue_cert("ca", &ca_key, Some((&root, &root_key)), true, |_| {});
+    let ec_ca_key = ec_pkey();
+    let ec_ca = issue_cert("ec ca", &ec_ca_key, Some((&root, &root_key)), true, |_| {});
+    let leaf = issue_cert(
+        "leaf",
+        &ec_pkey(),
+        Some((&sha256_ca, &ca_key)),
+        false,
+        |_| {},
+    );
+    let ec_leaf = issue_cert(
+        "leaf",
+        &ec_pkey(),
+        Some((&ec_ca, &ec_ca_key)),
+        false,
+        |_| {},
+    );
+    let store = store_with(&[&root]);
+    let param = X509VerifyParam::new().unwrap();
+
+    let mut policy = ChainPolicy::new();
+    policy
+        .set_allowed_signature_algorithms(&[Nid::ECDSA_WITH_SHA256, Nid::SHA256WITHRSAENCRYPTION]);
+
+    let mut chain = Stack::new().unwrap();
+    chain.push(sha256_ca).unwrap();
+    verify_chain(&leaf, &chain, &store, &param, &policy).unwrap();
+
+    let mut chain = Stack::new().unwrap();
+    chain.push(ec_ca).unwrap();
+    verify_chain(&ec_leaf, &chain, &store, &param, &policy).unwrap();
+
+    let mut chain = Stack::new().unwrap();
+    chain.push(sha1_ca).unwrap();
+    match verify_chain(&leaf, &chain, &store, &param, &policy) {
+        Err(ChainError::DisallowedSignatureAlgorithm { depth, nid }) => {
+            assert_eq!(depth, 1);
+            assert_eq!(nid, Nid::SHA1WITHRSAENCRYPTION);
+        }
+        r => panic!("unexpected result: {:?}", r.map(|_| ())),
+    }
+}
+
+#[test]
+fn test_verify_chain_inline_crl() {
+    let ca = X509::from_pem(include_bytes!("../../test/crl-ca.crt")).unwrap();
+    let revoked = X509::from_pem(include_bytes!("../../test/subca.crt")).unwrap();
+    let crl = X509Crl::from_der(include_bytes!("../../test/test.crl")).unwrap();
+    let chain = Stack::new().unwrap();
+    let store = store_with(&[&ca]);
+
+    // The fixture CRL is only current during the second half of 2019.
+    let mut param = X509VerifyParam::new().unwrap();
+    param.set_time(1567296000);
+
+    verify_chain(&revoked, &chain, &store, &param, &ChainPolicy::new()).unwrap();
+
+    let mut policy = ChainPolicy::new();
+    policy.add_crl(&crl);
+    match verify_chain(&revoked, &chain, &store, &param, &policy) {
+        Err(ChainError::Verify {
+            error: VerifyError::Revoked,
+            ..
+        }) => {}
+        r => panic!("unexpected result: {:?}", r.map(|_| ())),
+    }
+}
+
+#[test]
+fn test_verify_chain_is_wildcard() {
+    let root_key = pkey();
+    let root = issue_cert("root", &root_key, None, true, |_| {});
+    let leaf = |dns: &[&str]| {
+        issue_cert("leaf", &ec_pkey(), Some((&root, &root_key)), false, |b| {
+            let mut san = SubjectAlternativeName::new();
+            for name in dns {
+                san.dns(name);
+            }
+            let san = san.build(&b.x509v3_context(Some(&root), None)).unwrap();
+            b.append_extension(san).unwrap();
+        })
+    };
+    let chain = Stack::new().unwrap();
+    let store = store_with(&[&root]);
+    let param = X509VerifyParam::new().unwrap();
+    let policy = ChainPolicy::new();
+
+    let wildcard = leaf(&["example.com", "*.example.com"]);
+    let verified = verify_chain(&wildcard, &chain, &store, &param, &policy).unwrap();
+    assert!(verified.is_wildcard());
+
+    let plain = leaf(&["example.com", "www.example.com"]);
+    let verified = verify_chain(&plain, &chain, &store, &param, &policy).unwrap();
+    assert!(!verified.is_wildcard());
+}
+
+#[test]
+fn test_verify_chain_termination() {
+    let root_key = pkey();
+    let root = issue_cert("root", &root_key, None, true, |_| {});
+    let ca_key = pkey();
+    let ca = issue_cert("ca", &ca_key, Some((&root, &root_key)), true, |_| {});
+    let leaf = issue_cert("leaf", &pkey(), Some((&ca, &ca_key)), false, |_| {});
+    let mut chain = Stack::new().unwrap();
+    chain.push(ca.clone()).unwrap();
+    let policy = ChainPolicy::new();
+
+    let param = X509VerifyParam::new().unwrap();
+    let verified = verify_chain(&leaf, &chain, &store_with(&[&root]), &param, &policy).unwrap();
+    assert_eq!(verified.chain().len(), 3);
+    assert_eq!(verified.termination(), ChainTermination::SelfSignedRoot);
+
+    let mut param = X509VerifyParam::new().unwrap();
+    param.set_flags(X509VerifyFlags::PARTIAL_CHAIN).unwrap();
+    let empty = Stack::new().unwrap();
+    let verified = verify_chain(&leaf, &empty, &store_with(&[&ca]), &param, &policy).unwrap();
+    assert_eq!(verified.chain().len(), 2);
+    assert_eq!(
+        verified.termination(),
+        ChainTermination::TrustedIntermediate
+    );
+}
+
+#[test]
+fn test_verified_chain_to_pem() {
+    let root_key = pkey();
+    let root = issue_cert("root", &root_key, None, true, |_| {});
+    let ca_key = pkey();
+    let ca = issue_cert("ca", &ca_key, Some((&root, &root_key)), true, |_| {});
+    let leaf = issue_cert("leaf", &pkey(), Some((&ca, &ca_key)), false, |_| {});
+    let mut chain = Stack::new().unwrap();
+    chain.push(ca.clone()).unwrap();
+    let param = X509VerifyParam::new().unwrap();
+
+    let verified = verify_chain(
+        &leaf,
+        &chain,
+        &store_with(&[&root]),
+        &param,
+        &ChainPolicy::new(),
+    )
+    .unwrap();
+    let pem = verified.to_pem().unwrap();
+    let parsed = X509::stack_from_pem(&pem).unwrap();
+    assert_eq!(parsed.len(), 3);
+    for (parsed, expected) in parsed.iter().zip([&leaf, &ca, &root]) {
+        assert!(parsed == expected);
+    }
+}
+
+#[test]
+fn test_verify_chain_cross_signed() {
+    let old_key = pkey();
+    let old = issue_cert("old root", &old_key, None, true, |_| {});
+    let new_key = pkey();
+    let new = issue_cert("new root", &new_key, None, true, |_| {});
+    let cross = issue_cert("new root", &new_key, Some((&old, &old_key)), true, |_| {});
+    let ca_key = pkey();
+    let ca = issue_cert("ca", &ca_key, Some((&new, &new_key)), true, |_| {});
+    let leaf = issue_cert("leaf", &pkey(), Some((&ca, &ca_key)), false, |_| {});
+    let mut chain = Stack::new().unwrap();
+    chain.push(ca.clone()).unwrap();
+    chain.push(cross.clone()).unwrap();
+    let param = X509VerifyParam::new().unwrap();
+    let mut policy = ChainPolicy::new();
+
+    let both = store_with(&[&old, &new]);
+    let verified = verify_chain(&leaf, &chain, &both, &param, &policy).unwrap();
+    assert!(verified.anchor() == &*new);
+    assert!(!verified.shorter_path_available());
+
+    policy.set_untrusted_first(true);
+    let verified = verify_chain(&leaf, &chain, &both, &param, &policy).unwrap();
+    assert!(verified.anchor() == &*old);
+    assert_eq!(verified.chain().len(), 4);
+    assert!(verified.shorter_path_available());
+
+    let new_only = store_with(&[&new]);
+    let verified = verify_chain(&leaf, &chain, &new_only, &param, &policy).unwrap();
+    assert!(verified.anchor() == &*new);
+    assert!(!verified.shorter_path_available());
+
+    let mut param = X509VerifyParam::new().unwrap();
+    param.set_flags(X509VerifyFlags::NO_ALT_CHAINS).unwrap();
+    match verify_chain(&leaf, &chain, &new_only, &param, &policy) {
+        Err(ChainError::Verify { .. }) => {}
+        r => panic!("unexpected result: {:?}", r.map(|_| ())),
+    }
+}
+
+#[test]
+fn test_verify_chain_max_intermediates() {
+    let root_key = pkey();
+    let root = issue_cert("root", &root_key, None, true, |_| {});
+    let mut chain = Stack::new().unwrap();
+    let (mut issuer, mut issuer_key) = (root.clone(), root_key);
+    for cn in ["ca1", "ca2", "ca3"] {
+        let key = pkey();
+        let ca = issue_cert(cn, &key, Some((&issuer, &issuer_key)), true, |_| {});
+        chain.push(ca.clone()).unwrap();
+        (issuer, issuer_key) = (ca, key);
+    }
+    let leaf = issue_cert("leaf", &pkey(), Some((&issuer, &issuer_key)), false, |_| {});
+    let store = store_with(&[&root]);
+    let param = X509VerifyParam::new().unwrap();
+
+    let mut policy = ChainPolicy::new();
+    policy.set_max_intermediates(2);
+    match verify_chain(&leaf, &chain, &store, &param, &policy) {
+        Err(ChainError::TooManyIntermediates { count: 3 }) => {}
+        r => panic!("unexpected result: {:?}", r.map(|_| ())),
+    }
+
+    policy.set_max_intermediates(3);
+    verify_chain(&leaf, &chain, &store, &param, &policy).unwrap();
+}
+
+#[test]
+fn test_verify_bundle() {
+    let root_key = pkey();
+    let root = issue_cert("root", &root_key, None, true, |_| {});
+    let ca1_key = pkey();
+    let ca1 = issue_cert("ca1", &ca1_key, Some((&root, &root_key)), true, |_| {});
+    let ca2_key = pkey();
+    let ca2 = issue_cert("ca2", &ca2_key, Some((&ca1, &ca1_key)), true, |_| {});
+    let ca3 = issue_cert("ca3", &pkey(), Some((&ca2, &ca2_key)), true, |_| {});
+    let store = store_with(&[&root]);
+    let param = X509VerifyParam::new().unwrap();
+
+    // the bundle is accepted in any order
+    let mut bundle = Stack::new().unwrap();
+    bundle.push(ca1.clone()).unwrap();
+    bundle.push(ca3.clone()).unwrap();
+    bundle.push(ca2).unwrap();
+    let verified = verify_bundle(&bundle, &store, &param).unwrap();
+    let chain = verified.chain();
+    assert_eq!(chain.len(), 4);
+    assert!(chain.get(0).unwrap() == &*ca3);
+    assert!(chain.get(3).unwrap() == &*root);
+
+    // a gap is reported whichever piece of the bundle comes first
+    for gapped in [[&ca3, &ca1], [&ca1, &ca3]] {
+        let mut bundle = Stack::new().unwrap();
+        for cert in gapped {
+            bundle.push(cert.clone()).unwrap();
+        }
+        match verify_bundle(&bundle, &store, &param) {
+            Err(ChainError::MissingIssuer { depth, issuer }) => {
+                assert_eq!(depth, 0);
+                assert_eq!(issuer, "CN=ca2");
+            }
+            r => panic!("unexpected result: {:?}", r.map(|_| ())),
+        }
+    }
+
+    match verify_bundle(&Stack::new().unwrap(), &store, &param) {
+        Err(ChainError::EmptyBundle) => {}
+        r => panic!("unexpected result: {:?}", r.map(|_| ())),
+    }
+}
+
+#[test]
+fn test_verify_chain_error_depth() {
+    let root_key = pkey();
+    let root = issue_cert("root", &root_key, None, true, |_| {});
+    let ca_key = pkey();
+    let ca = issue_cert("ca", &ca_key, Some((&root, &root_key)), true, |b| {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as time_t;
+        let day = 24 * 60 * 60;
+        b.set_not_before(&Asn1Time::from_unix(now - 10 * day).unwrap())
+            .unwrap();
+        b.set_not_after(&Asn1Time::from_unix(now - day).unwrap())
+            .unwrap();
+    });
+    let leaf = issue_cert("leaf", &pkey(), Some((&ca, &ca_key)), false, |_| {});
+    let mut chain = Stack::new().unwrap();
+    chain.push(ca).unwrap();
+    let param = X509VerifyParam::new().unwrap();
+
+    let store = store_with(&[&root]);
+    match verify_chain(&leaf, &chain, &store, &param, &ChainPolicy::new()) {
+        Err(e @ ChainError::Verify { .. }) => {
+            assert_eq!(
+                e.to_string(),
+                "certificate verification failed at depth 1: certificate has expired"
+            );
+            match e {
+                ChainError::Verify { error, depth } => {
+                    assert_eq!(error, VerifyError::Expired);
+                    assert_eq!(depth, 1);
+                }
+                _ => unreachable!(),
+            }
+        }
+        r => panic!("unexpected result: {:?}", r.map(|_| ())),
+    }
+}
+
+#[test]
+fn test_verify_chain_report() {
+    let root_key = pkey();
+    let root = issue_cert("root", &root_key, None, true, |_| {});
+    let ca_key = pkey();
+    let ca = issue_cert("ca", &ca_key, Some((&root, &root_key)), true, |b| {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as time_t;
+        let day = 24 * 60 * 60;
+        b.set_not_before(&Asn1Time::from_unix(now - 10 * day).unwrap())
+            .unwrap();
+        b.set_not_after(&Asn1Time::from_unix(now - day).unwrap())
+            .unwrap();
+    });
+    let leaf = issue_cert("leaf", &ec_pkey(), Some((&ca, &ca_key)), false, |b| {
+        let san = SubjectAlternativeName::new()
+            .dns("example.com")
+            .build(&b.x509v3_context(Some(&ca), None))
+            .unwrap();
+        b.append_extension(san).unwrap();
+    });
+    let mut chain = Stack::new().unwrap();
+    chain.push(ca.clone()).unwrap();
+    let mut param = X509VerifyParam::new().unwrap();
+    param.set_host("example.com").unwrap();
+
+    let report = verify_chain_report(&leaf, &chain, &store_with(&[&root]), &param).unwrap();
+    assert_eq!(report.result, Err(VerifyError::Expired));
+    assert_eq!(report.termination, Some(ChainTermination::SelfSignedRoot));
+    #[cfg(ossl110)]
+    assert_eq!(report.matched_host.as_deref(), Some("example.com"));
+
+    let certs = &report.certificates;
+    assert_eq!(certs.len(), 3);
+    assert_eq!(certs[0].subject, "CN=leaf");
+    assert_eq!(certs[0].issuer, "CN=ca");
+    assert_eq!(certs[1].subject, "CN=ca");
+    assert_eq!(certs[1].not_after, ca.not_after().to_string());
+    assert_eq!(certs[2].subject, "CN=root");
+    assert!(certs[0].errors.is_empty());
+    assert_eq!(certs[1].errors, [VerifyError::Expired]);
+    assert!(certs[2].errors.is_empty());
+
+    let report = verify_chain_report(&leaf, &chain, &store_with(&[]), &param).unwrap();
+    assert_eq!(report.result, Err(VerifyError::UnableToGetIssuerLocally));
+    assert_eq!(report.termination, None);
+}
+
+#[test]
+#[cfg(ossl110)]
+fn test_verify_chain_required_key_usage() {
+    let root_key = pkey();
+    let root = issue_cert("root", &root_key, None, true, |_| {});
+    let leaf = issue_cert("leaf", &pkey(), Some((&root, &root_key)), false, |b| {
+        let usage = KeyUsage::new().digital_signature().build().unwrap();
+        b.append_extension(usage).unwrap();
+    });
+    let chain = Stack::new().unwrap();
+    let store = store_with(&[&root]);
+    let param = X509VerifyParam::new().unwrap();
+
+    let mut policy = ChainPolicy::new();
+    policy.set_required_key_usage(X509KeyUsage::KEY_ENCIPHERMENT);
+    match verify_chain(&leaf, &chain, &store, &param, &policy) {
+        Err(ChainError::MissingKeyUsage(usage)) => {
+            assert_eq!(usage, X509KeyUsage::KEY_ENCIPHERMENT)
+        }
+        r => panic!("unexpected result: {:?}", r.map(|_| ())),
+    }
+
+    policy.set_required_key_usage(X509KeyUsage::DIGITAL_SIGNATURE);
+    verify_chain(&leaf, &chain, &store, &param, &policy).unwrap();
+}
+
+#[test]
+fn test_verify_chain_dns_subtrees() {
+    let root_key = pkey();
+    let root = issue_cert("root", &root_key, None, true, |_| {});
+    let leaf = |dns: &str| {
+        issue_cert("leaf", &ec_pkey(), Some((&root, &root_key)), false, |b| {
+            let san = SubjectAlternativeName::new()
+                .dns(dns)
+                .build(&b.x509v3_context(Some(&root), None))
+                .unwrap();
+            b.append_extension(san).unwrap();
+        })
+    };
+    let chain = Stack::new().unwrap();
+    let store = store_with(&[&root]);
+    let param = X509VerifyParam::new().unwrap();
+    let mut policy = ChainPolicy::new();
+    policy.permit_dns_subtree(".internal.example.com");
+
+    let internal = leaf("db.internal.example.com");
+    verify_chain(&internal, &chain, &store, &param, &policy).unwrap();
+
+    let evil = leaf("evil.com");
+    match verify_chain(&evil, &chain, &store, &param, &policy) {
+        Err(ChainError::NameConstraint(name)) => assert_eq!(name, "evil.com"),
+        r => panic!("unexpected result: {:?}", r.map(|_| ())),
+    }
+
+    policy.exclude_dns_subtree("db.internal.example.com");
+    match verify_chain(&internal, &chain, &store, &param, &policy) {
+        Err(ChainError::NameConstraint(name)) => assert_eq!(name, "db.internal.example.com"),
+        r => panic!("unexpected result: {:?}", r.map(|_| ())),
+    }
+}
+
+#[test]
+fn test_verify_chain_require_dns_san() {
+    let root_key = pkey();
+    let root = issue_cert("root", &root_key, None, true, |_| {});
+    let leaf = |san: &mut SubjectAlternativeName| {
+        issue_cert("leaf", &ec_pkey(), Some((&root, &root_key)), false, |b| {
+            let san = san.build(&b.x509v3_context(Some(&root), None)).unwrap();
+            b.append_extension(san).unwrap();
+        })
+    };
+    let chain = Stack::new().unwrap();
+    let store = store_with(&[&root]);
+    let param = X509VerifyParam::new().unwrap();
+    let mut policy = ChainPolicy::new();
+    policy.set_require_dns_san(true);
+
+    let dns = leaf(SubjectAlternativeName::new().dns("example.com"));
+    verify_chain(&dns, &chain, &store, &param, &policy).unwrap();
+
+    let ip = leaf(SubjectAlternativeName::new().ip("192.0.2.1"));
+    verify_chain(&ip, &chain, &store, &param, &ChainPolicy::new()).unwrap();
+    match verify_chain(&ip, &chain, &store, &param, &policy) {
+        Err(ChainError::MissingDnsSan) => {}
+        r => panic!("unexpected result: {:?}", r.map(|_| ())),
+    }
+}
+
+#[test]
+fn test_verify_chain_with_pins() {
+    let root_key = pkey();
+    let root = issue_cert("root", &root_key, None, true, |_| {});
+    let ca_key = pkey();
+    let ca = issue_cert("ca", &ca_key, Some((&root, &root_key)), true, |_| {});
+    let leaf = issue_cert("leaf", &pkey(), Some((&ca, &ca_key)), false, |_| {});
+    let mut chain = Stack::new().unwrap();
+    chain.push(ca.clone()).unwrap();
+    let store = store_with(&[&root]);
+    let param = X509VerifyParam::new().unwrap();
+    let pin = |cert: &X509| {
+        let spki = cert.public_key().unwrap().public_key_to_der().unwrap();
+        let mut pin = [0; 32];
+        pin.copy_from_slice(&hash(MessageDigest::sha256(), &spki).unwrap());
+        pin
+    };
+
+    verify_chain_with_pins(&leaf, &chain, &store, &param, &[pin(&leaf)]).unwrap();
+
+    match verify_chain_with_pins(&leaf, &chain, &store, &param, &[[0; 32]]) {
+        Err(ChainError::PinMismatch) => {}
+        r => panic!("unexpected result: {:?}", r.map(|_| ())),
+    }
+
+    verify_chain_with_pins(&leaf, &chain, &store, &param, &[[0; 32], pin(&root)]).unwrap();
+
+    let empty = store_with(&[]);
+    match verify_chain_with_pins(&leaf, &chain, &empty, &param, &[pin(&leaf)]) {
+        Err(ChainError::Verify { .. }) => {}
+        r => panic!("unexpected result: {:?}", r.map(|_| ())),
+    }
+}
+
+#[test]
+#[allow(deprecated)]
+fn test_verify_chain_revocation_urls() {
+    let revocation = |ocsp: &'static str, crl: &'static str| {
+        move |b: &mut X509Builder| {
+            let aia = X509Extension::new_nid(None, None, Nid::INFO_ACCESS, ocsp).unwrap();
+            b.append_extension(aia).unwrap();
+            let cdp = X509Extension::new_nid(None, None, Nid::CRL_DISTRIBUTION_POINTS, crl);
+            b.append_extension(cdp.unwrap()).unwrap();
+        }
+    };
+    let root_key = pkey();
+    let root = issue_cert("root", &root_key, None, true, |_| {});
+    let ca_key = pkey();
+    let ca = issue_cert(
+        "ca",
+        &ca_key,
+        Some((&root, &root_key)),
+        true,
+        revocation(
+            "OCSP;URI:http://ocsp.example.com,caIssuers;URI:http://example.com/root.pem",
+            "URI:http://crl.example.com/root.crl",
+        ),
+    );
+    let leaf = issue_cert(
+        "leaf",
+        &pkey(),
+        Some((&ca, &ca_key)),
+        false,
+        revocation(
+            "OCSP;URI:http://ocsp.example.com",
+            "URI:http://crl.example.com/ca.crl",
+        ),
+    );
+    let mut chain = Stack::new().unwrap();
+    chain.push(ca.clone()).unwrap();
+    let param = X509VerifyParam::new().unwrap();
+    let policy = ChainPolicy::new();
+
+    let verified = verify_chain(&leaf, &chain, &store_with(&[&root]), &param, &policy).unwrap();
+    let urls = verified.revocation_urls();
+    assert_eq!(urls.ocsp, ["http://ocsp.example.com"]);
+    assert_eq!(
+        urls.crl,
+        [
+            "http://crl.example.com/ca.crl",
+            "http://crl.example.com/root.crl"
+        ]
+    );
+}
+
+#[test]
+fn test_verify_chain_host_matcher() {
+    let root_key = pkey();
+    let root = issue_cert("root", &root_key, None, true, |_| {});
+    let leaf = issue_cert("leaf", &ec_pkey(), Some((&root, &root_key)), false, |b| {
+        let san = SubjectAlternativeName::new()
+            .dns("example.com")
+            .build(&b.x509v3_context(Some(&root), None))
+            .unwrap();
+        b.append_extension(san).unwrap();
+    });
+    let chain = Stack::new().unwrap();
+    let store = store_with(&[&root]);
+    let param = X509VerifyParam::new().unwrap();
+
+    let mut policy = ChainPolicy::new();
+    policy.set_host_matcher("example.com.", |dns, name| dns == name);
+    match verify_chain(&leaf, &chain, &store, &param, &policy) {
+        Err(ChainError::Verify {
+            error: VerifyError::HostnameMismatch,
+            ..
+        }) => {}
+        r => panic!("unexpected result: {:?}", r.map(|_| ())),
+    }
+
+    policy.set_host_matcher("example.com.", |dns, name| {
+        dns.eq_ignore_ascii_case(name.strip_suffix('.').unwrap_or(name))
+    });
+    verify_chain(&leaf, &chain, &store, &param, &policy).unwrap();
+}
+
+#[test]
+fn test_verify_tsa_chain() {
+    let root_key = pkey();
+    let root = issue_cert("root", &root_key, None, true, |_| {});
+    let leaf = |eku: &mut ExtendedKeyUsage| {
+        issue_cert("leaf", &ec_pkey(), Some((&root, &root_key)), false, |b| {
+            b.append_extension(eku.critical().build().unwrap()).unwrap();
+        })
+    };
+    let chain = Stack::new().unwrap();
+    let store = store_with(&[&root]);
+    let param = X509VerifyParam::new().unwrap();
+
+    let tsa = leaf(ExtendedKeyUsage::new().time_stamping());
+    verify_tsa_chain(&tsa, &chain, &store, &param).unwrap();
+
+    let tls = leaf(ExtendedKeyUsage::new().server_auth());
+    match verify_tsa_chain(&tls, &chain, &store, &param) {
+        Err(ChainError::MissingEku(oid)) => assert_eq!(oid.nid(), Nid::TIME_STAMP),
+        r => panic!("unexpected result: {:?}", r.map(|_| ())),
+    }
+}
+
+#[test]
+fn test_verify_client_cert() {
+    let root_key = pkey();
+    let root = issue_cert("root", &root_key, None, true, |_| {});
+    let leaf = |eku: &mut ExtendedKeyUsage| {
+        issue_cert("alice", &ec_pkey(), Some((&root, &root_key)), false, |b| {
+            b.append_extension(eku.build().unwrap()).unwrap();
+        })
+    };
+    let chain = Stack::new().unwrap();
+    let store = store_with(&[&root]);
+    let param = X509VerifyParam::new().unwrap();
+
+    let client = leaf(ExtendedKeyUsage::new().client_auth());
+    let subject = verify_client_cert(&client, &chain, &store, &param).unwrap();
+    assert_eq!(subject, "CN=alice");
+
+    let server = leaf(ExtendedKeyUsage::new().server_auth());
+    match verify_client_cert(&server, &chain, &store, &param) {
+        Err(ChainError::MissingEku(oid)) => assert_eq!(oid.nid(), Nid::CLIENT_AUTH),
+        r => panic!("unexpected result: {:?}", r.map(|_| ())),
+    }
+
+    let untrusted = store_with(&[]);
+    match verify_client_cert(&client, &chain, &untrusted, &param) {
+        Err(ChainError::Verify { .. }) => {}
+        r => panic!("unexpected result: {:?}", r.map(|_| ())),
+    }
+}
+
+#[test]
+fn test_verify_code_signing() {
+    let root_key = pkey();
+    let root = issue_cert("root", &root_key, None, true, |_| {});
+    let leaf = |eku: &mut ExtendedKeyUsage| {
+        issue_cert(
+            "publisher",
+            &ec_pkey(),
+            Some((&root, &root_key)),
+            false,
+            |b| {
+                b.append_extension(eku.build().unwrap()).unwrap();
+            },
+        )
+    };
+    let chain = Stack::new().unwrap();
+    let store = store_with(&[&root]);
+    let param = X509VerifyParam::new().unwrap();
+
+    let signing = leaf(ExtendedKeyUsage::new().code_signing());
+    let signer = verify_code_signing(&signing, &chain, &store, &param).unwrap();
+    assert_eq!(signer.subject(), "CN=publisher");
+    assert_eq!(signer.not_before(), signing.not_before());
+    assert_eq!(signer.not_after(), signing.not_after());
+    assert_eq!(signer.chain().chain().len(), 2);
+
+    let tls = leaf(ExtendedKeyUsage::new().server_auth());
+    match verify_code_signing(&tls, &chain, &store, &param) {
+        Err(ChainError::MissingEku(oid)) => assert_eq!(oid.nid(), Nid::CODE_SIGN),
+        r => panic!("unexpected result: {:?}", r.map(|_| ())),
+    }
+}
+
+#[test]
+fn test_verify_chain_with_ssl_param() {
+    let root_key = pkey();
+    let root = issue_cert("root", &root_key, None, true, |_| {});
+    let leaf_for = |host: &str| {
+        issue_cert(host, &pkey(), Some((&root, &root_key)), false, |b| {
+            let san = SubjectAlternativeName::new()
+                .dns(host)
+                .build(&b.x509v3_context(Some(&root), None))
+                .unwrap();
+            b.append_extension(san).unwrap();
+        })
+    };
+    let chain = Stack::new().unwrap();
+    let store = store_with(&[&root]);
+
+    let mut ctx = SslContext::builder(SslMethod::tls()).unwrap();
+    ctx.verify_param_mut()
+        .set_flags(X509VerifyFlags::CHECK_SS_SIGNATURE)
+        .unwrap();
+    let ctx = ctx.build();
+    let mut ssl = Ssl::new(&ctx).unwrap();
+    ssl.param_mut().set_host("foo.example.com").unwrap();
+
+    let mut ctx_param = ctx.verify_param().to_owned().unwrap();
+    assert!(ctx_param
+        .flags()
+        .contains(X509VerifyFlags::CHECK_SS_SIGNATURE));
+
+    let mut param = ssl.param().to_owned().unwrap();
+    assert!(param.flags().contains(X509VerifyFlags::CHECK_SS_SIGNATURE));
+    let policy = ChainPolicy::new();
+    verify_chain(
+        &leaf_for("foo.example.com"),
+        &chain,
+        &store,
+        &param,
+        &policy,
+    )
+    .unwrap();
+    match verify_chain(
+        &leaf_for("bar.example.com"),
+        &chain,
+        &store,
+        &param,
+        &policy,
+    ) {
+        Err(ChainError::Verify {
+            error: VerifyError::HostnameMismatch,
+            ..
+        }) => {}
+        r => panic!("unexpected result: {:?}", r.map(|_| ())),
+    }
+}
+
+#[test]
+#[cfg(not(osslconf = "OPENSSL_NO_OCSP"))]
+fn test_verify_chain_stapled_ocsp() {
+    let ca = X509::from_pem(include_bytes!("../../test/ocsp_staple_ca.pem")).unwrap();
+    let good = X509::from_pem(include_bytes!("../../test/ocsp_staple_good.pem")).unwrap();
+    let revoked = X509::from_pem(include_bytes!("../../test/ocsp_staple_revoked.pem")).unwrap();
+    let good_resp = include_bytes!("../../test/ocsp_staple_resp_good.der");
+    let good_resp = OcspResponse::from_der(good_resp).unwrap();
+    let revoked_resp = include_bytes!("../../test/ocsp_staple_resp_revoked.der");
+    let revoked_resp = OcspResponse::from_der(revoked_resp).unwrap();
+    let chain = Stack::new().unwrap();
+    let store = store_with(&[&ca]);
+    let param = X509VerifyParam::new().unwrap();
+    let policy = ChainPolicy::new();
+
+    let verified = verify_chain(&good, &chain, &store, &param, &policy).unwrap();
+    let stapled = verified.verify_stapled_ocsp(&good_resp, &store).unwrap();
+    let status = stapled.status();
+    assert_eq!(status.status, OcspCertStatus::GOOD);
+    assert!(status.next_update().is_some());
+    match verified.verify_stapled_ocsp(&revoked_resp, &store) {
+        Err(ChainError::MissingOcspStatus) => {}
+        r => panic!("unexpected result: {:?}", r.map(|_| ())),
+    }
+
+    let verified = verify_chain(&revoked, &chain, &store, &param, &policy).unwrap();
+    let stapled = verified.verify_stapled_ocsp(&revoked_resp, &store).unwrap();
+    let status = stapled.status();
+    assert_eq!(status.status, OcspCertStatus::REVOKED);
+    assert_eq!(status.reason, OcspRevokedStatus::KEY_COMPROMISE);
+    assert!(status.revocation_time.is_some());
+
+    let other = store_with(&[&issue_cert("other", &pkey(), None, true, |_| {})]);
+    assert!(verified.verify_stapled_ocsp(&revoked_resp, &other).is_err());
+}
+
+#[test]
+fn test_verify_chain_max_leaf_validity() {
+    let root_key = pkey();
+    let root = issue_cert("root", &root_key, None, true, |_| {});
+    let leaf = issue_cert("leaf", &pkey(), Some((&root, &root_key)), false, |b| {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as time_t;
+        b.set_not_before(&Asn1Time::from_unix(now).unwrap())
+            .unwrap();
+        let not_after = Asn1Time::from_unix(now + 3650 * 24 * 60 * 60).unwrap();
+        b.set_not_after(&not_after).unwrap();
+    });
+    let chain = Stack::new().unwrap();
+    let store = store_with(&[&root]);
+    let param = X509VerifyParam::new().unwrap();
+
+    verify_chain(&leaf, &chain, &store, &param, &ChainPolicy::new()).unwrap();
+
+    let mut policy = ChainPolicy::new();
+    policy.set_max_leaf_validity_days(398);
+    match verify_chain(&leaf, &chain, &store, &param, &policy) {
+        Err(ChainError::ValidityTooLong { days: 3650 }) => {}
+        r => panic!("unexpected result: {:?}", r.map(|_| ())),
+    }
+
+    policy.set_max_leaf_validity_days(3650);
+    verify_chain(&leaf, &chain, &store, &param, &policy).unwrap();
+}
+
+#[test]
+fn test_verify_chain_allowed_errors() {
+    let root_key = pkey();
+    let root = issue_cert("root", &root_key, None, true, |_| {});
+    let leaf = issue_cert("leaf", &pkey(), Some((&root, &root_key)), false, |_| {});
+    let chain = Stack::new().unwrap();
+    let store = store_with(&[&root]);
+
+    let mut param = X509VerifyParam::new().unwrap();
+    param.set_flags(X509VerifyFlags::CRL_CHECK).unwrap();
+
+    match verify_chain(&leaf, &chain, &store, &param, &ChainPolicy::new()) {
+        Err(ChainError::Verify {
+            error: VerifyError::UnableToGetCrl,
+            ..
+        }) => {}
+        r => panic!("unexpected result: {:?}", r.map(|_| ())),
+    }
+
+    let mut policy = ChainPolicy::new();
+    policy.allow_error(VerifyError::UnableToGetCrl);
+    verify_chain(&leaf, &chain, &store, &param, &policy).unwrap();
+
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+    param.set_time((now.as_secs() + 400 * 24 * 60 * 60) as time_t);
+    match verify_chain(&leaf, &chain, &store, &param, &policy) {
+        Err(ChainError::Verify {
+            error: VerifyError::Expired,
+            ..
+        }) => {}
+        r => panic!("unexpected result: {:?}", r.map(|_| ())),
+    }
+}
+
+#[test]
+#[cfg(ossl110)]
+fn test_verify_chain_require_ca() {
+    let root_key = pkey();
+    let root = issue_cert("root", &root_key, None, true, |_| {});
+    let ca_key = pkey();
+    let ca = issue_cert("ca", &ca_key, Some((&root, &root_key)), false, |b| {
+        let bc = BasicConstraints::new().critical().ca().pathlen(0).build();
+        b.append_extension(bc.unwrap()).unwrap();
+    });
+    let leaf = issue_cert("leaf", &ec_pkey(), Some((&ca, &ca_key)), false, |_| {});
+    let mut chain = Stack::new().unwrap();
+    chain.push(ca).unwrap();
+
+    let mut policy = ChainPolicy::new();
+    policy.set_require_ca(true);
+    let param = X509VerifyParam::new().unwrap();
+
+    verify_chain(&leaf, &chain, &store_with(&[&root]), &param, &policy).unwrap();
+
+    // OpenSSL accepts a trust anchor asserting keyCertSign without basicConstraints.
+    let not_ca_key = pkey();
+    let not_ca = issue_cert("not ca", &not_ca_key, None, false, |b| {
+        let ku = KeyUsage::new().critical().key_cert_sign().build();
+        b.append_extension(ku.unwrap()).unwrap();
+    });
+    let leaf = issue_cert(
+        "leaf",
+        &ec_pkey(),
+        Some((&not_ca, &not_ca_key)),
+        false,
+        |_| {},
+    );
+    let chain = Stack::new().unwrap();
+    let store = store_with(&[&not_ca]);
+
+    verify_chain(&leaf, &chain, &store, &param, &ChainPolicy::new()).unwrap();
+    match verify_chain(&leaf, &chain, &store, &param, &policy) {
+        Err(ChainError::NotCa { depth }) => assert_eq!(depth, 1),
+        r => panic!("unexpected result: {:?}", r.map(|_| ())),
+    }
+}
+
+#[test]
+#[cfg(all(
+    ossl300,
+    not(osslconf = "OPENSSL_NO_SM2"),
+    not(osslconf = "OPENSSL_NO_SM3")
+))]
+fn test_verify_chain_sm2_default_id() {
+    use crate::asn1::Asn1OctetString;
+    use crate::md::Md;
+    use crate::md_ctx::MdCtx;
+    use crate::pkey::Id;
+    use crate::pkey_ctx::PkeyCtx;
+    use foreign_types::ForeignTypeRef;
+
+    const DEFAULT_ID: &[u8] = b"1234567812345678";
+
+    let mut ctx = PkeyCtx::new_id(Id::SM2).unwrap();
+    ctx.keygen_init().unwrap();
+    let root_key = ctx.keygen().unwrap();
+    let leaf_key = ctx.keygen().unwrap();
+
+    let root = issue_cert_with_digest("root", &root_key, None, true, MessageDigest::sm3(), |_| {});
+    let mut leaf = issue_cert_with_digest(
+        "leaf",
+        &leaf_key,
+        Some((&root, &root_key)),
+        false,
+        MessageDigest::sm3(),
+        |_| {},
+    );
+    // sign the leaf again with the default ID, as a GM/T 0009-2012 issuer would
+    let mut md_ctx = MdCtx::new().unwrap();
+    let pctx = md_ctx.digest_sign_init(Some(Md::sm3()), &root_key).unwrap();
+    unsafe {
+        assert_eq!(
+            ffi::EVP_PKEY_CTX_set1_id(pctx.as_ptr(), DEFAULT_ID.as_ptr() as *const _, 16),
+            1
+        );
+        assert!(ffi::X509_sign_ctx(leaf.as_ptr(), md_ctx.as_ptr()) > 0);
+    }
+    assert_eq!(
+        leaf.signature_algorithm().object().to_string(),
+        "SM2-with-SM3"
+    );
+
+    let store = store_with(&[&root]);
+    let param = X509VerifyParam::new().unwrap();
+    let chain = Stack::new().unwrap();
+
+    // OpenSSL verifies with an empty ID unless one is attached
+    match verify_chain(&leaf, &chain, &store, &param, &ChainPolicy::new()) {
+        Err(ChainError::Verify {
+            error: VerifyError::SignatureFailure,
+            depth: 0,
+        }) => {}
+        r => panic!("unexpected result: {:?}", r.map(|_| ())),
+    }
+
+    leaf.set_sm2_default_id().unwrap();
+    let verified = verify_chain(&leaf, &chain, &store, &param, &ChainPolicy::new()).unwrap();
+    assert_eq!(verified.chain().len(), 2);
+
+    unsafe {
+        let id = Asn1OctetString::new_from_bytes(b"another id").unwrap();
+        ffi::X509_set0_distinguishing_id(leaf.as_ptr(), id.as_ptr());
+        std::mem::forget(id);
+    }
+    assert!(verify_chain(&leaf, &chain, &store, &param, &ChainPolicy::new()).is_err());
+}
+
+#[test]
+fn test_verify_chain_leaf_validity() {
+    let root_key = pkey();
+    let root = issue_cert("root", &root_key, None, true, |_| {});
+    let not_after = Asn1Time::days_from_now(30).unwrap();
+    let leaf = issue_cert("leaf", &ec_pkey(), Some((&root, &root_key)), false, |b| {
+        b.set_not_after(&not_after).unwrap();
+    });
+    let store = store_with(&[&root]);
+    let param = X509VerifyParam::new().unwrap();
+    let chain = Stack::new().unwrap();
+
+    let verified = verify_chain(&leaf, &chain, &store, &param, &ChainPolicy::new()).unwrap();
+    assert!(verified.not_after() == not_after);
+    assert!(verified.not_before() == leaf.not_before());
+}
+
+#[test]
+fn test_verify_chain_signature_algorithms() {
+    let root_key = pkey();
+    let root = issue_cert("root", &root_key, None, true, |_| {});
+    let ca_key = ec_pkey();
+    let ca = issue_cert("ca", &ca_key, Some((&root, &root_key)), true, |_| {});
+    let leaf = issue_cert("leaf", &pkey(), Some((&ca, &ca_key)), false, |_| {});
+    let mut chain = Stack::new().unwrap();
+    chain.push(ca).unwrap();
+    let store = store_with(&[&root]);
+    let param = X509VerifyParam::new().unwrap();
+
+    let verified = verify_chain(&leaf, &chain, &store, &param, &ChainPolicy::new()).unwrap();
+    assert_eq!(
+        verified.signature_algorithms(),
+        [
+            Nid::ECDSA_WITH_SHA256,
+            Nid::SHA256WITHRSAENCRYPTION,
+            Nid::SHA256WITHRSAENCRYPTION,
+        ]
+    );
+}
+
+#[test]
+fn test_verify_param_pin_exact_host() {
+    let root_key = pkey();
+    let root = issue_cert("root", &root_key, None, true, |_| {});
+    let leaf = |dns: &str| {
+        issue_cert("leaf", &ec_pkey(), Some((&root, &root_key)), false, |b| {
+            let san = SubjectAlternativeName::new()
+                .dns(dns)
+                .build(&b.x509v3_context(Some(&root), None))
+                .unwrap();
+            b.append_extension(san).unwrap();
+        })
+    };
+    let wildcard = leaf("*.example.com");
+    let exact = leaf("a.example.com");
+    let chain = Stack::new().unwrap();
+    let store = store_with(&[&root]);
+    let policy = ChainPolicy::new();
+
+    let mut param = X509VerifyParam::new().unwrap();
+    param.set_host("a.example.com").unwrap();
+    verify_chain(&wildcard, &chain, &store, &param, &policy).unwrap();
+
+    let mut param = X509VerifyParam::new().unwrap();
+    param.pin_exact_host("a.example.com").unwrap();
+    verify_chain(&exact, &chain, &store, &param, &policy).unwrap();
+    match verify_chain(&wildcard, &chain, &store, &param, &policy) {
+        Err(ChainError::Verify {
+            error: VerifyError::HostnameMismatch,
+            ..
+        }) => {}
+        r => panic!("unexpected result: {:?}", r.map(|_| ())),
+    }
+}
+
+#[test]
+fn test_verify_param_expected_host() {
+    let mut param = X509VerifyParam::new().unwrap();
+    assert_eq!(param.expected_host(), None);
+
+    param.set_host("a.example.com").unwrap();
+    assert_eq!(param.expected_host().as_deref(), Some("a.example.com"));
+
+    let copy = param.to_owned().unwrap();
+    assert_eq!(copy.expected_host().as_deref(), Some("a.example.com"));
+
+    param.set_host("").unwrap();
+    assert_eq!(param.expected_host(), None);
+    assert_eq!(copy.expected_host().as_deref(), Some("a.example.com"));
+}
+
+#[test]
+#[cfg(ossl110)]
+fn test_verify_param_default_profile() {
+    let root_key = pkey();
+    let root = issue_cert("root", &root_key, None, true, |_| {});
+    let leaf = |eku: &ExtendedKeyUsage| {
+        issue_cert("leaf", &ec_pkey(), Some((&root, &root_key)), false, |b| {
+            b.append_extension(eku.build().unwrap()).unwrap();
+        })
+    };
+    let store = store_with(&[&root]);
+    let chain = Stack::new().unwrap();
+    let policy = ChainPolicy::new();
+
+    let mut param = X509VerifyParam::new().unwrap();
+    param.set_flags(X509VerifyFlags::X509_STRICT).unwrap();
+    param.set_default_profile("ssl_server").unwrap();
+    assert_eq!(param.flags(), X509VerifyFlags::empty());
+
+    let server = leaf(ExtendedKeyUsage::new().server_auth());
+    verify_chain(&server, &chain, &store, &param, &policy).unwrap();
+    let client = leaf(ExtendedKeyUsage::new().client_auth());
+    match verify_chain(&client, &chain, &store, &param, &policy) {
+        Err(ChainError::Verify {
+            error: VerifyError::InvalidPurpose,
+            ..
+        }) => {}
+        r => panic!("unexpected result: {:?}", r.map(|_| ())),
+    }
+
+    assert!(param.set_default_profile("no_such_profile").is_err());
+}
+
+#[test]
+fn test_verify_param_builder() {
+    let root_key = pkey();
+    let root = issue_cert("root", &root_key, None, true, |_| {});
+    let leaf = issue_cert("leaf", &ec_pkey(), Some((&root, &root_key)), false, |b| {
+        let san = SubjectAlternativeName::new()
+            .dns("example.com")
+            .build(&b.x509v3_context(Some(&root), None))
+            .unwrap();
+        b.append_extension(san).unwrap();
+    });
+    let store = store_with(&[&root]);
+    let chain = Stack::new().unwrap();
+
+    let mut param = X509VerifyParam::builder()
+        .host("example.com")
+        .flags(X509VerifyFlags::CHECK_SS_SIGNATURE)
+        .depth(1)
+        .build()
+        .unwrap();
+    assert!(param.flags().contains(X509VerifyFlags::CHECK_SS_SIGNATURE));
+    verify_chain(&leaf, &chain, &store, &param, &ChainPolicy::new()).unwrap();
+
+    let param = X509VerifyParam::builder()
+        .host("example.org")
+        .depth(1)
+        .build()
+        .unwrap();
+    assert!(verify_chain(&leaf, &chain, &store, &param, &ChainPolicy::new()).is_err());
+
+    assert!(X509VerifyParam::builder()
+        .host("example\0.com")
+        .depth(1)
+        .build()
+        .is_err());
+}
+
+#[test]
+fn test_check_host_rust() {
+    fn cert(cn: &str, dns: &[&str]) -> X509 {
+        let key = ec_pkey();
+        issue_cert(cn, &key, None, false, |b| {
+            if !dns.is_empty() {
+                let mut san = SubjectAlternativeName::new();
+                for name in dns {
+                    san.dns(name);
+                }
+                let san = san.build(&b.x509v3_context(None, None)).unwrap();
+                b.append_extension(san).unwrap();
+            }
+        })
+    }
+    let none = X509CheckFlags::empty();
+
+    // RFC 6125 section 6.4.3 examples.
+    let wildcard = cert("unused", &["*.example.com"]);
+    assert!(check_host_rust(&wildcard, "foo.example.com", none));
+    assert!(check_host_rust(&wildcard, "FOO.Example.COM", none));
+    assert!(!check_host_rust(&wildcard, "bar.foo.example.com", none));
+    assert!(!check_host_rust(&wildcard, "example.com", none));
+
+    let partial = cert(
+        "unused",
+        &["baz*.example.net", "*baz.example.org", "b*z.example.com"],
+    );
+    assert!(check_host_rust(&partial, "baz1.example.net", none));
+    assert!(check_host_rust(&partial, "foobaz.example.org", none));
+    assert!(check_host_rust(&partial, "buzz.example.com", none));
+    assert!(!check_host_rust(&partial, "buzz.example.net", none));
+    assert!(!check_host_rust(
+        &partial,
+        "baz1.example.net",
+        X509CheckFlags::NO_PARTIAL_WILDCARDS
+    ));
+    assert!(!check_host_rust(
+        &wildcard,
+        "foo.example.com",
+        X509CheckFlags::NO_WILDCARDS
+    ));
+
+    // Wildcards need at least two labels after them and must be left-most.
+    let bad = cert("unused", &["*.com", "foo.*.example.com"]);
+    assert!(!check_host_rust(&bad, "example.com", none));
+    assert!(!check_host_rust(&bad, "foo.bar.example.com", none));
+
+    // Trailing dots are ignored and IP literals never match.
+    let exact = cert("unused", &["www.example.com.", "127.0.0.1"]);
+    assert!(check_host_rust(&exact, "www.example.com", none));
+    assert!(check_host_rust(&exact, "www.example.com.", none));
+    assert!(!check_host_rust(&exact, "127.0.0.1", none));
+
+    // The subject CN is only a fallback when there are no DNS names.
+    let cn_only = cert("www.example.com", &[]);
+    assert!(check_host_rust(&cn_only, "www.example.com", none));
+    assert!(!check_host_rust(&exact, "unused", none));
+    assert!(check_host_rust(
+        &exact,
+        "unused",
+        X509CheckFlags::ALWAYS_CHECK_SUBJECT
+    ));
+    #[cfg(ossl110)]
+    assert!(!check_host_rust(
+        &cn_only,
+        "www.example.com",
+        X509CheckFlags::NEVER_CHECK_SUBJECT
+    ));
+}
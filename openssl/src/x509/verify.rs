@@ -1,11 +1,39 @@
 use bitflags::bitflags;
 use foreign_types::ForeignTypeRef;
-use libc::{c_int, c_uint, c_ulong, time_t};
+use libc::{c_int, c_uint, c_ulong, c_void, time_t};
+#[cfg(not(ossl300))]
+use std::collections::HashMap;
+use std::error::Error;
+#[cfg(ossl110)]
+use std::ffi::CStr;
+#[cfg(ossl110)]
+use std::ffi::CString;
+use std::fmt;
+use std::io;
 use std::net::IpAddr;
+use std::ptr;
+use std::sync::Arc;
+#[cfg(not(ossl300))]
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
 
+use crate::asn1::{Asn1Object, Asn1ObjectRef, Asn1TimeRef};
 use crate::error::ErrorStack;
-use crate::x509::X509PurposeId;
-use crate::{cvt, cvt_p};
+use crate::hash::{hash, MessageDigest};
+use crate::nid::Nid;
+#[cfg(not(osslconf = "OPENSSL_NO_OCSP"))]
+use crate::ocsp::{OcspBasicResponse, OcspCertId, OcspFlag, OcspResponseRef, OcspStatus};
+use crate::pkey::Id;
+use crate::stack::{Stack, StackRef};
+use crate::x509::store::X509StoreRef;
+use crate::x509::{
+    X509Crl, X509CrlRef, X509NameRef, X509PurposeId, X509Ref, X509StoreContext, X509VerifyResult,
+    X509,
+};
+use crate::{cvt, cvt_n, cvt_p};
+#[cfg(not(ossl300))]
+use once_cell::sync::Lazy;
+use once_cell::sync::OnceCell;
 use openssl_macros::corresponds;
 
 bitflags! {
@@ -51,37 +79,101 @@ bitflags! {
         #[cfg(ossl110)]
         const SUITEB_128_LOS_ONLY = ffi::X509_V_FLAG_SUITEB_128_LOS_ONLY;
         #[cfg(ossl110)]
-        const SUITEB_192_LOS = ffi::X509_V_FLAG_SUITEB_128_LOS;
+        const SUITEB_192_LOS = ffi::X509_V_FLAG_SUITEB_192_LOS;
         #[cfg(ossl110)]
-        const SUITEB_128_LOS = ffi::X509_V_FLAG_SUITEB_192_LOS;
+        const SUITEB_128_LOS = ffi::X509_V_FLAG_SUITEB_128_LOS;
         const PARTIAL_CHAIN = ffi::X509_V_FLAG_PARTIAL_CHAIN as _;
         const NO_ALT_CHAINS = ffi::X509_V_FLAG_NO_ALT_CHAINS as _;
         const NO_CHECK_TIME = ffi::X509_V_FLAG_NO_CHECK_TIME as _;
     }
 }
 
+bitflags! {
+    /// Usages from the key usage extension of an `X509` certificate.
+    #[derive(Copy, Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    #[repr(transparent)]
+    pub struct X509KeyUsage: u32 {
+        const DIGITAL_SIGNATURE = ffi::X509v3_KU_DIGITAL_SIGNATURE;
+        const NON_REPUDIATION = ffi::X509v3_KU_NON_REPUDIATION;
+        const KEY_ENCIPHERMENT = ffi::X509v3_KU_KEY_ENCIPHERMENT;
+        const DATA_ENCIPHERMENT = ffi::X509v3_KU_DATA_ENCIPHERMENT;
+        const KEY_AGREEMENT = ffi::X509v3_KU_KEY_AGREEMENT;
+        const KEY_CERT_SIGN = ffi::X509v3_KU_KEY_CERT_SIGN;
+        const CRL_SIGN = ffi::X509v3_KU_CRL_SIGN;
+        const ENCIPHER_ONLY = ffi::X509v3_KU_ENCIPHER_ONLY;
+        const DECIPHER_ONLY = ffi::X509v3_KU_DECIPHER_ONLY;
+    }
+}
+
 foreign_type_and_impl_send_sync! {
     type CType = ffi::X509_VERIFY_PARAM;
-    fn drop = ffi::X509_VERIFY_PARAM_free;
+    fn drop = free_verify_param;
 
     /// Adjust parameters associated with certificate verification.
+    ///
+    /// There is no parameter for the SM2 distinguishing ID, which OpenSSL takes from the
+    /// certificate object being verified instead. Use [`X509Ref::set_sm2_default_id`] on each
+    /// SM2-signed certificate to verify it with the default ID from GM/T 0009-2012.
     pub struct X509VerifyParam;
     /// Reference to `X509VerifyParam`.
     pub struct X509VerifyParamRef;
 }
 
+// OpenSSL has no getter for the expected host before 3.0, so it is recorded here for every
+// `X509VerifyParam` while it is alive. Parameters owned by OpenSSL objects are not tracked, since
+// nothing would remove their entries once OpenSSL frees them.
+#[cfg(not(ossl300))]
+static EXPECTED_HOSTS: Lazy<Mutex<HashMap<usize, Option<String>>>> = Lazy::new(Default::default);
+
+#[cfg(not(ossl300))]
+fn record_expected_host(param: *mut ffi::X509_VERIFY_PARAM, host: Option<String>) {
+    if let Some(slot) = EXPECTED_HOSTS.lock().unwrap().get_mut(&(param as usize)) {
+        *slot = host;
+    }
+}
+
+unsafe fn free_verify_param(param: *mut ffi::X509_VERIFY_PARAM) {
+    #[cfg(not(ossl300))]
+    EXPECTED_HOSTS.lock().unwrap().remove(&(param as usize));
+    ffi::X509_VERIFY_PARAM_free(param);
+}
+
 impl X509VerifyParam {
     /// Create an X509VerifyParam
     #[corresponds(X509_VERIFY_PARAM_new)]
     pub fn new() -> Result<X509VerifyParam, ErrorStack> {
         unsafe {
             ffi::init();
-            cvt_p(ffi::X509_VERIFY_PARAM_new()).map(X509VerifyParam)
+            let param = cvt_p(ffi::X509_VERIFY_PARAM_new())?;
+            #[cfg(not(ossl300))]
+            EXPECTED_HOSTS.lock().unwrap().insert(param as usize, None);
+            Ok(X509VerifyParam(param))
         }
     }
+
+    /// Returns a builder which configures a new `X509VerifyParam` in one chain of calls.
+    pub fn builder() -> X509VerifyParamBuilder {
+        X509VerifyParamBuilder::new()
+    }
 }
 
 impl X509VerifyParamRef {
+    /// Returns an owned copy of the parameters.
+    ///
+    /// This allows the parameters of an [`SslRef`](crate::ssl::SslRef) or
+    /// [`SslContextRef`](crate::ssl::SslContextRef) to be reused with [`verify_chain`], for
+    /// example to reproduce the certificate checks of a failed handshake offline.
+    #[corresponds(X509_VERIFY_PARAM_set1)]
+    pub fn to_owned(&self) -> Result<X509VerifyParam, ErrorStack> {
+        let param = X509VerifyParam::new()?;
+        unsafe {
+            cvt(ffi::X509_VERIFY_PARAM_set1(param.as_ptr(), self.as_ptr()))?;
+        }
+        #[cfg(not(ossl300))]
+        record_expected_host(param.as_ptr(), self.expected_host());
+        Ok(param)
+    }
+
     /// Set the host flags.
     #[corresponds(X509_VERIFY_PARAM_set_hostflags)]
     pub fn set_hostflags(&mut self, hostflags: X509CheckFlags) {
@@ -131,9 +223,53 @@ impl X509VerifyParamRef {
                 self.as_ptr(),
                 raw_host.as_ptr() as *const _,
                 host.len(),
-            ))
-            .map(|_| ())
+            ))?;
+        }
+        #[cfg(not(ossl300))]
+        record_expected_host(
+            self.as_ptr(),
+            Some(host.to_string()).filter(|host| !host.is_empty()),
+        );
+        Ok(())
+    }
+
+    /// Returns the expected DNS hostname, if one has been set.
+    ///
+    /// If several hostnames are configured, the first one is returned. This can be compared with
+    /// the SNI name sent on a connection to detect configuration mistakes.
+    ///
+    /// Before OpenSSL 3.0.0, which has no getter for the host, only a host set with
+    /// [`set_host`][Self::set_host] on an [`X509VerifyParam`] is reported. `None` is always
+    /// returned for parameters borrowed from other objects, such as
+    /// [`SslRef::param_mut`](crate::ssl::SslRef::param_mut).
+    #[corresponds(X509_VERIFY_PARAM_get0_host)]
+    pub fn expected_host(&self) -> Option<String> {
+        #[cfg(ossl300)]
+        unsafe {
+            let host = ffi::X509_VERIFY_PARAM_get0_host(self.as_ptr(), 0);
+            if host.is_null() {
+                None
+            } else {
+                Some(CStr::from_ptr(host).to_string_lossy().into_owned())
+            }
         }
+        #[cfg(not(ossl300))]
+        EXPECTED_HOSTS
+            .lock()
+            .unwrap()
+            .get(&(self.as_ptr() as usize))
+            .cloned()
+            .flatten()
+    }
+
+    /// Set the expected DNS hostname and require the certificate to name it exactly.
+    ///
+    /// This is intended for pinned connections to a single host. It replaces the host flags with
+    /// [`X509CheckFlags::NO_WILDCARDS`] and [`X509CheckFlags::NO_PARTIAL_WILDCARDS`], so a
+    /// certificate for `*.example.com` does not match `a.example.com`.
+    pub fn pin_exact_host(&mut self, host: &str) -> Result<(), ErrorStack> {
+        self.set_hostflags(X509CheckFlags::NO_WILDCARDS | X509CheckFlags::NO_PARTIAL_WILDCARDS);
+        self.set_host(host)
     }
 
     /// Set the expected email address.
@@ -181,12 +317,35 @@ impl X509VerifyParamRef {
         unsafe { ffi::X509_VERIFY_PARAM_set_time(self.as_ptr(), time) }
     }
 
+    /// Sets the verification time from a [`SystemTime`].
+    ///
+    /// Certificates are then checked against `time` rather than the current time, which allows
+    /// archived chains to be validated as of a historical date. Like [`set_time`][Self::set_time],
+    /// this also sets [`X509VerifyFlags::USE_CHECK_TIME`]. Sub-second precision is discarded, and
+    /// an error is returned if `time` does not fit in a `time_t`.
+    #[corresponds(X509_VERIFY_PARAM_set_time)]
+    pub fn set_system_time(&mut self, time: SystemTime) -> Result<(), ErrorStack> {
+        let time = match time.duration_since(UNIX_EPOCH) {
+            Ok(since) => time_t::try_from(since.as_secs()),
+            Err(before) => time_t::try_from(before.duration().as_secs()).map(|secs| -secs),
+        }
+        .map_err(|_| ErrorStack::raise(ffi::ERR_LIB_ASN1, ffi::ASN1_R_ERROR_GETTING_TIME))?;
+        self.set_time(time);
+        Ok(())
+    }
+
     /// Set the verification depth
     #[corresponds(X509_VERIFY_PARAM_set_depth)]
     pub fn set_depth(&mut self, depth: c_int) {
         unsafe { ffi::X509_VERIFY_PARAM_set_depth(self.as_ptr(), depth) }
     }
 
+    /// Gets the verification depth, or -1 if it has not been set.
+    #[corresponds(X509_VERIFY_PARAM_get_depth)]
+    pub fn depth(&self) -> c_int {
+        unsafe { ffi::X509_VERIFY_PARAM_get_depth(self.as_ptr()) }
+    }
+
     /// Sets the authentication security level to auth_level
     #[corresponds(X509_VERIFY_PARAM_set_auth_level)]
     #[cfg(ossl110)]
@@ -201,9 +360,1635 @@ impl X509VerifyParamRef {
         unsafe { ffi::X509_VERIFY_PARAM_get_auth_level(self.as_ptr()) }
     }
 
+    /// Resets the parameters to the library's built-in profile called `name`.
+    ///
+    /// The profiles available in every supported OpenSSL version are `default`, `pkcs7`,
+    /// `smime_sign`, `ssl_client` and `ssl_server`. Each setting, including the verification flags
+    /// and any expected host, is replaced by the profile's value, so further settings should be
+    /// applied afterwards. An error is returned if no profile is called `name`.
+    ///
+    /// Requires OpenSSL 1.1.0 or newer.
+    #[corresponds(X509_VERIFY_PARAM_lookup)]
+    #[cfg(ossl110)]
+    pub fn set_default_profile(&mut self, name: &str) -> Result<(), ErrorStack> {
+        let name = CString::new(name)
+            .map_err(|_| ErrorStack::raise(ffi::ERR_LIB_X509, ffi::X509_R_UNKNOWN_PURPOSE_ID))?;
+        unsafe {
+            let profile = ffi::X509_VERIFY_PARAM_lookup(name.as_ptr());
+            if profile.is_null() {
+                return Err(ErrorStack::raise(
+                    ffi::ERR_LIB_X509,
+                    ffi::X509_R_UNKNOWN_PURPOSE_ID,
+                ));
+            }
+
+            let inh_flags = ffi::X509_VERIFY_PARAM_get_inh_flags(self.as_ptr());
+            ffi::X509_VERIFY_PARAM_set_inh_flags(
+                self.as_ptr(),
+                ffi::X509_VP_FLAG_OVERWRITE | ffi::X509_VP_FLAG_RESET_FLAGS,
+            );
+            let result = cvt(ffi::X509_VERIFY_PARAM_inherit(self.as_ptr(), profile));
+            ffi::X509_VERIFY_PARAM_set_inh_flags(self.as_ptr(), inh_flags);
+            result?;
+            cvt(ffi::X509_VERIFY_PARAM_set1_name(
+                self.as_ptr(),
+                name.as_ptr(),
+            ))?;
+        }
+
+        Ok(())
+    }
+
     /// Sets the verification purpose
     #[corresponds(X509_VERIFY_PARAM_set_purpose)]
     pub fn set_purpose(&mut self, purpose: X509PurposeId) -> Result<(), ErrorStack> {
         unsafe { cvt(ffi::X509_VERIFY_PARAM_set_purpose(self.as_ptr(), purpose.0)).map(|_| ()) }
     }
 }
+
+/// A builder for [`X509VerifyParam`].
+///
+/// Settings are collected and applied by [`build`][Self::build], which returns the first error
+/// encountered.
+#[derive(Clone, Debug, Default)]
+pub struct X509VerifyParamBuilder {
+    host: Option<String>,
+    ip: Option<IpAddr>,
+    flags: Option<X509VerifyFlags>,
+    depth: Option<c_int>,
+    time: Option<time_t>,
+    #[cfg(ossl110)]
+    auth_level: Option<c_int>,
+}
+
+impl X509VerifyParamBuilder {
+    /// Creates a builder with no settings.
+    pub fn new() -> X509VerifyParamBuilder {
+        X509VerifyParamBuilder::default()
+    }
+
+    /// Sets the expected DNS hostname.
+    pub fn host(&mut self, host: &str) -> &mut X509VerifyParamBuilder {
+        self.host = Some(host.to_string());
+        self
+    }
+
+    /// Sets the expected IPv4 or IPv6 address.
+    pub fn ip(&mut self, ip: IpAddr) -> &mut X509VerifyParamBuilder {
+        self.ip = Some(ip);
+        self
+    }
+
+    /// Sets verification flags.
+    pub fn flags(&mut self, flags: X509VerifyFlags) -> &mut X509VerifyParamBuilder {
+        self.flags = Some(flags);
+        self
+    }
+
+    /// Sets the verification depth.
+    pub fn depth(&mut self, depth: c_int) -> &mut X509VerifyParamBuilder {
+        self.depth = Some(depth);
+        self
+    }
+
+    /// Sets the verification time, in seconds since the epoch.
+    pub fn time(&mut self, time: time_t) -> &mut X509VerifyParamBuilder {
+        self.time = Some(time);
+        self
+    }
+
+    /// Sets the authentication security level.
+    #[cfg(ossl110)]
+    pub fn auth_level(&mut self, lvl: c_int) -> &mut X509VerifyParamBuilder {
+        self.auth_level = Some(lvl);
+        self
+    }
+
+    /// Creates the `X509VerifyParam`.
+    pub fn build(&self) -> Result<X509VerifyParam, ErrorStack> {
+        let mut param = X509VerifyParam::new()?;
+        if let Some(host) = &self.host {
+            param.set_host(host)?;
+        }
+        if let Some(ip) = self.ip {
+            param.set_ip(ip)?;
+        }
+        if let Some(flags) = self.flags {
+            param.set_flags(flags)?;
+        }
+        if let Some(depth) = self.depth {
+            param.set_depth(depth);
+        }
+        if let Some(time) = self.time {
+            param.set_time(time);
+        }
+        #[cfg(ossl110)]
+        if let Some(lvl) = self.auth_level {
+            param.set_auth_level(lvl);
+        }
+        Ok(param)
+    }
+}
+
+/// Additional checks applied by [`verify_chain`] once OpenSSL has built and validated a chain.
+///
+/// A default policy performs no additional checks.
+#[derive(Clone, Debug, Default)]
+pub struct ChainPolicy {
+    allowed_errors: Vec<VerifyError>,
+    crls: Vec<X509Crl>,
+    max_intermediates: Option<usize>,
+    max_leaf_validity_days: Option<u32>,
+    min_rsa_bits: Option<u32>,
+    permitted_dns_subtrees: Vec<String>,
+    excluded_dns_subtrees: Vec<String>,
+    host_matcher: Option<HostMatcher>,
+    required_eku: Option<Asn1Object>,
+    #[cfg(ossl110)]
+    required_key_usage: X509KeyUsage,
+    #[cfg(ossl110)]
+    require_ca: bool,
+    reject_sha1_signatures: bool,
+    allowed_signature_algorithms: Option<Vec<Nid>>,
+    require_dns_san: bool,
+    untrusted_first: bool,
+}
+
+impl ChainPolicy {
+    /// Creates a policy which performs no additional checks.
+    pub fn new() -> ChainPolicy {
+        ChainPolicy::default()
+    }
+
+    /// Accepts the chain even if OpenSSL reports `error` while verifying it.
+    ///
+    /// Verification continues past an allowed error, so a chain which also fails for any other
+    /// reason is still rejected with that error. This can be called multiple times to allow
+    /// several errors; [`VerifyError::Other`] can be used for codes without a dedicated variant.
+    ///
+    /// Allowing errors installs a verify callback on the verification context, which replaces
+    /// any verify callback configured on the store passed to [`verify_chain`].
+    pub fn allow_error(&mut self, error: VerifyError) {
+        self.allowed_errors.push(error);
+    }
+
+    /// Checks the revocation status of the leaf against `crl` during verification.
+    ///
+    /// The CRLs added here are only used by [`verify_chain`] calls made with this policy, so the
+    /// shared [`X509Store`](crate::x509::store::X509Store) is not modified. Adding a CRL enables
+    /// [`X509VerifyFlags::CRL_CHECK`]; set [`X509VerifyFlags::CRL_CHECK_ALL`] on the parameters
+    /// to check the whole chain. A revoked certificate is reported by OpenSSL as a
+    /// [`ChainError::Verify`] error.
+    pub fn add_crl(&mut self, crl: &X509CrlRef) {
+        self.crls.push(crl.to_owned());
+    }
+
+    /// Rejects the chain if it contains more than `max` intermediate certificates.
+    ///
+    /// Unlike [`X509VerifyParamRef::set_depth`], neither the leaf nor the trust anchor is counted,
+    /// so a limit on intermediates can be enforced independently of how the chain is anchored.
+    pub fn set_max_intermediates(&mut self, max: usize) {
+        self.max_intermediates = Some(max);
+    }
+
+    /// Rejects the chain if the leaf's validity period, from notBefore to notAfter, is longer than
+    /// `days` days.
+    ///
+    /// This allows enforcing limits such as the 398 days accepted by browsers for TLS server
+    /// certificates. Intermediates and the trust anchor are not checked.
+    pub fn set_max_leaf_validity_days(&mut self, days: u32) {
+        self.max_leaf_validity_days = Some(days);
+    }
+
+    /// Rejects the chain if any certificate in it has an RSA key smaller than `bits`.
+    ///
+    /// Unlike [`X509VerifyParamRef::set_auth_level`], this only considers RSA keys and applies
+    /// the exact threshold given.
+    pub fn set_min_rsa_bits(&mut self, bits: u32) {
+        self.min_rsa_bits = Some(bits);
+    }
+
+    /// Rejects the chain unless every DNS name in the leaf falls within one of the permitted
+    /// subtrees.
+    ///
+    /// This applies a name constraint on top of any encoded in the CA certificates, for example to
+    /// restrict a private CA to a subdomain. Subtrees follow the `dNSName` rules of RFC 5280:
+    /// `example.com` matches that name and all of its subdomains, while `.example.com` only
+    /// matches subdomains. The leaf's DNS subject alternative names are checked, or its subject
+    /// common names if it has none. This can be called multiple times to permit several subtrees.
+    pub fn permit_dns_subtree(&mut self, subtree: &str) {
+        self.permitted_dns_subtrees.push(subtree.to_string());
+    }
+
+    /// Rejects the chain if any DNS name in the leaf falls within the excluded subtree.
+    ///
+    /// Subtrees are matched as described in [`permit_dns_subtree`](Self::permit_dns_subtree), and
+    /// exclusions take precedence over permitted subtrees.
+    pub fn exclude_dns_subtree(&mut self, subtree: &str) {
+        self.excluded_dns_subtrees.push(subtree.to_string());
+    }
+
+    /// Checks the leaf against the host name `name` using `matcher` rather than OpenSSL's rules.
+    ///
+    /// `matcher` is called with each DNS subject alternative name of the leaf, or each subject
+    /// common name if it has none, followed by `name`. The leaf matches if any call returns
+    /// `true`, and the chain is otherwise rejected with [`VerifyError::HostnameMismatch`]. A host
+    /// set on the verification parameters is still checked by OpenSSL, so only one of the two
+    /// should be used.
+    pub fn set_host_matcher<F>(&mut self, name: &str, matcher: F)
+    where
+        F: Fn(&str, &str) -> bool + Send + Sync + 'static,
+    {
+        self.host_matcher = Some(HostMatcher {
+            name: name.to_string(),
+            matcher: Arc::new(matcher),
+        });
+    }
+
+    /// Rejects the chain unless the leaf's extended key usage extension lists `oid`.
+    ///
+    /// Unlike [`X509VerifyParamRef::set_purpose`], this accepts arbitrary OIDs. A leaf without the
+    /// extension is rejected, and `anyExtendedKeyUsage` does not match other OIDs.
+    pub fn set_required_eku(&mut self, oid: &Asn1ObjectRef) {
+        self.required_eku = Some(oid.to_owned());
+    }
+
+    /// Rejects the chain unless the leaf's key usage extension asserts every usage in `usage`.
+    ///
+    /// For example, TLS 1.3 requires [`X509KeyUsage::DIGITAL_SIGNATURE`] and RSA key transport
+    /// requires [`X509KeyUsage::KEY_ENCIPHERMENT`]. As specified by RFC 5280, a leaf without the
+    /// extension may be used for any purpose and is accepted.
+    ///
+    /// Requires OpenSSL 1.1.0 or newer.
+    #[cfg(ossl110)]
+    pub fn set_required_key_usage(&mut self, usage: X509KeyUsage) {
+        self.required_key_usage = usage;
+    }
+
+    /// Rejects the chain unless every certificate above the leaf, including the trust anchor, has
+    /// a basicConstraints extension asserting `CA:TRUE` and a path length constraint which allows
+    /// the certificates below it.
+    ///
+    /// Unless [`X509VerifyFlags::X509_STRICT`] is set, OpenSSL accepts trust anchors without
+    /// basicConstraints, such as version 1 certificates or ones asserting the `keyCertSign` key
+    /// usage, and versions before 3.0 accept such intermediates as well. Unlike OpenSSL, this
+    /// check also counts self-issued intermediates against the path length constraint.
+    #[cfg(ossl110)]
+    pub fn set_require_ca(&mut self, require: bool) {
+        self.require_ca = require;
+    }
+
+    /// Rejects the chain if any certificate in it is signed using a SHA-1 based algorithm.
+    ///
+    /// The signature on the trust anchor is not checked, since the anchor is trusted directly.
+    /// Unlike raising the [authentication level](X509VerifyParamRef::set_auth_level), this only
+    /// affects SHA-1 and behaves the same on every OpenSSL version.
+    pub fn set_reject_sha1_signatures(&mut self, reject: bool) {
+        self.reject_sha1_signatures = reject;
+    }
+
+    /// Rejects the chain if any certificate in it is signed using an algorithm not listed in
+    /// `algorithms`, such as [`Nid::ECDSA_WITH_SHA256`] or [`Nid::SHA256WITHRSAENCRYPTION`].
+    ///
+    /// As with [`set_reject_sha1_signatures`](Self::set_reject_sha1_signatures), the signature on
+    /// the trust anchor is not checked. Replaces any previously allowed algorithms.
+    pub fn set_allowed_signature_algorithms(&mut self, algorithms: &[Nid]) {
+        self.allowed_signature_algorithms = Some(algorithms.to_vec());
+    }
+
+    /// Rejects the chain unless the leaf has at least one `dNSName` subject alternative name.
+    ///
+    /// This rejects leaves identified only by IP address or email subject alternative names, which
+    /// cannot be used for name-based virtual hosts. Subject common names are not considered.
+    pub fn set_require_dns_san(&mut self, require: bool) {
+        self.require_dns_san = require;
+    }
+
+    /// Builds the chain from the untrusted intermediates before consulting the trusted store.
+    ///
+    /// By default OpenSSL looks for each issuer in the trusted store first, so a root cross-signed
+    /// by another root is never used if the chain can be anchored at the root directly. If this
+    /// is set, the untrusted certificates are followed first, as OpenSSL did before 1.1.0. If that
+    /// path does not end in a trusted certificate, OpenSSL then looks for an alternative chain
+    /// unless [`X509VerifyFlags::NO_ALT_CHAINS`] is set.
+    pub fn set_untrusted_first(&mut self, untrusted_first: bool) {
+        self.untrusted_first = untrusted_first;
+    }
+
+    fn check(&self, chain: &StackRef<X509>) -> Result<(), ChainError> {
+        if let (Some(oid), Some(leaf)) = (&self.required_eku, chain.get(0)) {
+            if !has_eku(leaf, oid) {
+                return Err(ChainError::MissingEku(oid.clone()));
+            }
+        }
+
+        #[cfg(ossl110)]
+        if let Some(leaf) = chain.get(0) {
+            let usage = unsafe { ffi::X509_get_key_usage(leaf.as_ptr()) };
+            let missing = self.required_key_usage - X509KeyUsage::from_bits_retain(usage);
+            if !missing.is_empty() {
+                return Err(ChainError::MissingKeyUsage(missing));
+            }
+        }
+
+        if let Some(leaf) = chain.get(0) {
+            self.check_dns_subtrees(leaf)?;
+        }
+
+        if let (true, Some(leaf)) = (self.require_dns_san, chain.get(0)) {
+            let has_dns = leaf
+                .subject_alt_names()
+                .is_some_and(|sans| sans.iter().any(|san| san.dnsname().is_some()));
+            if !has_dns {
+                return Err(ChainError::MissingDnsSan);
+            }
+        }
+
+        if let (Some(host), Some(leaf)) = (&self.host_matcher, chain.get(0)) {
+            if !leaf_dns_names(leaf)
+                .iter()
+                .any(|dns| (host.matcher)(dns, &host.name))
+            {
+                return Err(ChainError::Verify {
+                    error: VerifyError::HostnameMismatch,
+                    depth: 0,
+                });
+            }
+        }
+
+        if let Some(max) = self.max_intermediates {
+            let count = chain.len().saturating_sub(2);
+            if count > max {
+                return Err(ChainError::TooManyIntermediates { count });
+            }
+        }
+
+        if let (Some(max_days), Some(leaf)) = (self.max_leaf_validity_days, chain.get(0)) {
+            let validity = leaf.not_before().diff(leaf.not_after())?;
+            let days = validity.days + (validity.secs > 0) as c_int;
+            if days > 0 && days as u32 > max_days {
+                return Err(ChainError::ValidityTooLong { days: days as u32 });
+            }
+        }
+
+        for (depth, cert) in chain.iter().enumerate() {
+            if let Some(min_bits) = self.min_rsa_bits {
+                let key = cert.public_key()?;
+                if key.id() == Id::RSA && key.bits() < min_bits {
+                    return Err(ChainError::RsaKeyTooSmall {
+                        depth,
+                        bits: key.bits(),
+                    });
+                }
+            }
+
+            if let (Some(allowed), true) =
+                (&self.allowed_signature_algorithms, depth + 1 < chain.len())
+            {
+                let nid = cert.signature_algorithm().object().nid();
+                if !allowed.contains(&nid) {
+                    return Err(ChainError::DisallowedSignatureAlgorithm { depth, nid });
+                }
+            }
+
+            if self.reject_sha1_signatures && depth + 1 < chain.len() {
+                let algs = cert
+                    .signature_algorithm()
+                    .object()
+                    .nid()
+                    .signature_algorithms();
+                if algs.is_some_and(|algs| algs.digest == Nid::SHA1) {
+                    return Err(ChainError::Sha1Signature { depth });
+                }
+            }
+
+            #[cfg(ossl110)]
+            if self.require_ca && depth > 0 {
+                let flags = unsafe { ffi::X509_get_extension_flags(cert.as_ptr()) };
+                if flags & ffi::EXFLAG_CA == 0 {
+                    return Err(ChainError::NotCa { depth });
+                }
+                if let Some(pathlen) = cert.pathlen() {
+                    if depth - 1 > pathlen as usize {
+                        return Err(ChainError::PathLenExceeded { depth, pathlen });
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn check_dns_subtrees(&self, leaf: &X509Ref) -> Result<(), ChainError> {
+        if self.permitted_dns_subtrees.is_empty() && self.excluded_dns_subtrees.is_empty() {
+            return Ok(());
+        }
+
+        for name in leaf_dns_names(leaf) {
+            let excluded = self
+                .excluded_dns_subtrees
+                .iter()
+                .any(|subtree| dns_in_subtree(&name, subtree));
+            let permitted = self.permitted_dns_subtrees.is_empty()
+                || self
+                    .permitted_dns_subtrees
+                    .iter()
+                    .any(|subtree| dns_in_subtree(&name, subtree));
+            if excluded || !permitted {
+                return Err(ChainError::NameConstraint(name));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn has_eku(cert: &X509Ref, oid: &Asn1ObjectRef) -> bool {
+    let ekus = cert.extended_key_usage();
+    ekus.iter().flatten().any(|eku| eku == oid)
+}
+
+type HostMatchFn = dyn Fn(&str, &str) -> bool + Send + Sync;
+
+#[derive(Clone)]
+struct HostMatcher {
+    name: String,
+    matcher: Arc<HostMatchFn>,
+}
+
+impl fmt::Debug for HostMatcher {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("HostMatcher")
+            .field("name", &self.name)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Returns the leaf's DNS subject alternative names, or its subject common names if it has none.
+fn leaf_dns_names(leaf: &X509Ref) -> Vec<String> {
+    let names = leaf
+        .subject_alt_names()
+        .map(|sans| {
+            sans.iter()
+                .filter_map(|san| san.dnsname().map(str::to_string))
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    if !names.is_empty() {
+        return names;
+    }
+
+    leaf.subject_name()
+        .entries_by_nid(Nid::COMMONNAME)
+        .filter_map(|cn| cn.data().as_utf8().ok())
+        .map(|cn| cn.to_string())
+        .collect()
+}
+
+fn dns_in_subtree(name: &str, subtree: &str) -> bool {
+    let name = name.strip_suffix('.').unwrap_or(name).as_bytes();
+    let subtree = subtree.as_bytes();
+    if subtree.is_empty() {
+        return true;
+    }
+    if name.len() < subtree.len() {
+        return false;
+    }
+
+    let (prefix, suffix) = name.split_at(name.len() - subtree.len());
+    if !suffix.eq_ignore_ascii_case(subtree) {
+        return false;
+    }
+    if subtree[0] == b'.' {
+        !prefix.is_empty()
+    } else {
+        prefix.is_empty() || prefix.ends_with(b".")
+    }
+}
+
+/// How a chain validated by [`verify_chain`] ends.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ChainTermination {
+    /// The trust anchor is a self-signed root certificate.
+    SelfSignedRoot,
+    /// The trust anchor is a certificate issued by someone else, such as an intermediate trusted
+    /// directly with [`X509VerifyFlags::PARTIAL_CHAIN`].
+    TrustedIntermediate,
+}
+
+/// A certificate chain which has been successfully validated by [`verify_chain`].
+pub struct VerifiedChain {
+    chain: Stack<X509>,
+    termination: ChainTermination,
+    shorter_path_available: bool,
+}
+
+impl VerifiedChain {
+    /// Returns the constructed chain, ordered from the leaf to the trust anchor.
+    pub fn chain(&self) -> &StackRef<X509> {
+        &self.chain
+    }
+
+    /// Returns whether the chain ends at a self-signed root or at another trusted certificate.
+    ///
+    /// A trust anchor counts as self-signed if it issued itself and its signature verifies with
+    /// its own public key.
+    pub fn termination(&self) -> ChainTermination {
+        self.termination
+    }
+
+    /// Returns the leaf certificate of the chain.
+    pub fn leaf(&self) -> &X509Ref {
+        self.chain.get(0).expect("verified chain is never empty")
+    }
+
+    /// Returns the trust anchor the chain was built to.
+    ///
+    /// When a root has been cross-signed by another root, this identifies which of the two paths
+    /// OpenSSL selected.
+    pub fn anchor(&self) -> &X509Ref {
+        self.chain
+            .get(self.chain.len() - 1)
+            .expect("verified chain is never empty")
+    }
+
+    /// Returns whether the trusted store holds an issuer for a certificate below the anchor's
+    /// direct subordinate, so a shorter chain could have been built.
+    ///
+    /// This is typically the case when the chain passes through a cross-signed certificate to an
+    /// older root although the newer root is trusted as well. Serving a shorter chain avoids the
+    /// extra certificate and signature check.
+    pub fn shorter_path_available(&self) -> bool {
+        self.shorter_path_available
+    }
+
+    /// Returns whether any DNS subject alternative name of the leaf is a wildcard.
+    ///
+    /// A name is considered a wildcard if its left-most label contains `*`, which includes partial
+    /// wildcards such as `b*z.example.com`. The subject common name is not consulted.
+    pub fn is_wildcard(&self) -> bool {
+        self.leaf().subject_alt_names().is_some_and(|sans| {
+            sans.iter().filter_map(|san| san.dnsname()).any(|dns| {
+                dns.split('.')
+                    .next()
+                    .is_some_and(|label| label.contains('*'))
+            })
+        })
+    }
+
+    /// Returns the start of the leaf certificate's validity period.
+    pub fn not_before(&self) -> &Asn1TimeRef {
+        self.leaf().not_before()
+    }
+
+    /// Returns the end of the leaf certificate's validity period.
+    ///
+    /// This is useful for scheduling renewal of the leaf certificate.
+    pub fn not_after(&self) -> &Asn1TimeRef {
+        self.leaf().not_after()
+    }
+
+    /// Serializes the chain to concatenated PEM-encoded certificates, ordered from the leaf to the
+    /// trust anchor.
+    ///
+    /// The output can be parsed with [`X509::stack_from_pem`], e.g. to reproduce the
+    /// verification elsewhere.
+    pub fn to_pem(&self) -> Result<Vec<u8>, ErrorStack> {
+        let mut pem = vec![];
+        for cert in &self.chain {
+            pem.extend_from_slice(&cert.to_pem()?);
+        }
+        Ok(pem)
+    }
+
+    /// Returns the revocation URLs listed by the certificates in the chain.
+    ///
+    /// OCSP responders are taken from the authority information access extension and CRLs from
+    /// the full names of the CRL distribution points. Only URIs are collected, in chain order from
+    /// the leaf, and each URL is reported once even if several certificates list it.
+    pub fn revocation_urls(&self) -> RevocationUrls {
+        let mut urls = RevocationUrls::default();
+        for cert in &self.chain {
+            for desc in cert.authority_info().iter().flatten() {
+                if desc.method().nid() != Nid::AD_OCSP {
+                    continue;
+                }
+                if let Some(uri) = desc.location().uri() {
+                    push_unique(&mut urls.ocsp, uri);
+                }
+            }
+
+            for dp in cert.crl_distribution_points().iter().flatten() {
+                let names = dp.distpoint().and_then(|name| name.fullname());
+                for uri in names
+                    .iter()
+                    .flat_map(|names| names.iter())
+                    .filter_map(|n| n.uri())
+                {
+                    push_unique(&mut urls.crl, uri);
+                }
+            }
+        }
+        urls
+    }
+
+    /// Returns the signature algorithm of each certificate in the chain, ordered from the leaf to
+    /// the trust anchor.
+    pub fn signature_algorithms(&self) -> Vec<Nid> {
+        self.chain
+            .iter()
+            .map(|cert| cert.signature_algorithm().object().nid())
+            .collect()
+    }
+
+    /// Verifies a stapled OCSP response for the leaf certificate.
+    ///
+    /// The response must be signed by the leaf's issuer, or by a responder it delegated to, with a
+    /// certificate chaining to `roots`. Its thisUpdate and nextUpdate times are checked against
+    /// the current time, allowing five minutes of clock skew. The response may identify the leaf
+    /// using either a SHA-1 or a SHA-256 certificate ID.
+    ///
+    /// A revoked leaf is not reported as an error; check the status of the returned
+    /// [`StapledOcsp`].
+    #[cfg(not(osslconf = "OPENSSL_NO_OCSP"))]
+    pub fn verify_stapled_ocsp(
+        &self,
+        response: &OcspResponseRef,
+        roots: &X509StoreRef,
+    ) -> Result<StapledOcsp, ChainError> {
+        let leaf = self.leaf();
+        let issuer = self.chain.get(1).unwrap_or(leaf);
+
+        let basic = response.basic()?;
+        basic.verify(&self.chain, roots, OcspFlag::empty())?;
+
+        for digest in [MessageDigest::sha1(), MessageDigest::sha256()] {
+            let id = OcspCertId::from_cert(digest, leaf, issuer)?;
+            let found = match basic.find_status(&id) {
+                Some(status) => {
+                    status.check_validity(OCSP_VALIDITY_SLACK, None)?;
+                    true
+                }
+                None => false,
+            };
+            if found {
+                return Ok(StapledOcsp { basic, id });
+            }
+        }
+
+        Err(ChainError::MissingOcspStatus)
+    }
+}
+
+#[cfg(not(osslconf = "OPENSSL_NO_OCSP"))]
+const OCSP_VALIDITY_SLACK: u32 = 5 * 60;
+
+/// Revocation URLs collected from a chain by [`VerifiedChain::revocation_urls`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RevocationUrls {
+    /// The OCSP responder URLs.
+    pub ocsp: Vec<String>,
+    /// The CRL distribution point URLs.
+    pub crl: Vec<String>,
+}
+
+fn push_unique(urls: &mut Vec<String>, url: &str) {
+    if !urls.iter().any(|u| u == url) {
+        urls.push(url.to_string());
+    }
+}
+
+/// A stapled OCSP response which has been verified by [`VerifiedChain::verify_stapled_ocsp`].
+#[cfg(not(osslconf = "OPENSSL_NO_OCSP"))]
+pub struct StapledOcsp {
+    basic: OcspBasicResponse,
+    id: OcspCertId,
+}
+
+#[cfg(not(osslconf = "OPENSSL_NO_OCSP"))]
+impl StapledOcsp {
+    /// Returns the leaf's revocation status, along with the response's thisUpdate and nextUpdate
+    /// times.
+    pub fn status(&self) -> OcspStatus<'_> {
+        self.basic
+            .find_status(&self.id)
+            .expect("verified response contains the leaf's status")
+    }
+}
+
+/// The reason OpenSSL rejected a chain in [`verify_chain`].
+///
+/// Common `X509_V_ERR_*` codes have their own variants; all other codes are reported as
+/// [`VerifyError::Other`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum VerifyError {
+    /// The issuer of a certificate could not be found (`X509_V_ERR_UNABLE_TO_GET_ISSUER_CERT`).
+    UnableToGetIssuer,
+    /// No CRL was found for a certificate (`X509_V_ERR_UNABLE_TO_GET_CRL`).
+    UnableToGetCrl,
+    /// A certificate's signature is invalid (`X509_V_ERR_CERT_SIGNATURE_FAILURE`).
+    SignatureFailure,
+    /// A certificate is not yet valid (`X509_V_ERR_CERT_NOT_YET_VALID`).
+    NotYetValid,
+    /// A certificate has expired (`X509_V_ERR_CERT_HAS_EXPIRED`).
+    Expired,
+    /// A CRL is not yet valid (`X509_V_ERR_CRL_NOT_YET_VALID`).
+    CrlNotYetValid,
+    /// A CRL has expired (`X509_V_ERR_CRL_HAS_EXPIRED`).
+    CrlExpired,
+    /// The leaf is self-signed and not trusted (`X509_V_ERR_DEPTH_ZERO_SELF_SIGNED_CERT`).
+    SelfSignedLeaf,
+    /// The chain ends in a self-signed root which is not trusted
+    /// (`X509_V_ERR_SELF_SIGNED_CERT_IN_CHAIN`).
+    UntrustedRoot,
+    /// The issuer of a certificate is not in the trusted store
+    /// (`X509_V_ERR_UNABLE_TO_GET_ISSUER_CERT_LOCALLY`).
+    UnableToGetIssuerLocally,
+    /// The chain is longer than the maximum verification depth (`X509_V_ERR_CERT_CHAIN_TOO_LONG`).
+    ChainTooLong,
+    /// A certificate has been revoked (`X509_V_ERR_CERT_REVOKED`).
+    Revoked,
+    /// A CA certificate is invalid (`X509_V_ERR_INVALID_CA`).
+    InvalidCa,
+    /// A path length constraint was exceeded (`X509_V_ERR_PATH_LENGTH_EXCEEDED`).
+    PathLengthExceeded,
+    /// A certificate cannot be used for the requested purpose (`X509_V_ERR_INVALID_PURPOSE`).
+    InvalidPurpose,
+    /// The root is not trusted for the requested purpose (`X509_V_ERR_CERT_UNTRUSTED`).
+    Untrusted,
+    /// The root is marked to reject the requested purpose (`X509_V_ERR_CERT_REJECTED`).
+    Rejected,
+    /// The leaf does not match the expected host name (`X509_V_ERR_HOSTNAME_MISMATCH`).
+    HostnameMismatch,
+    /// The leaf does not match the expected email address (`X509_V_ERR_EMAIL_MISMATCH`).
+    EmailMismatch,
+    /// The leaf does not match the expected IP address (`X509_V_ERR_IP_ADDRESS_MISMATCH`).
+    IpAddressMismatch,
+    /// Any other `X509_V_ERR_*` code.
+    Other(c_int),
+}
+
+impl VerifyError {
+    /// Creates a `VerifyError` from an `X509_V_ERR_*` code.
+    pub fn from_raw(code: c_int) -> VerifyError {
+        match code {
+            ffi::X509_V_ERR_UNABLE_TO_GET_ISSUER_CERT => VerifyError::UnableToGetIssuer,
+            ffi::X509_V_ERR_UNABLE_TO_GET_CRL => VerifyError::UnableToGetCrl,
+            ffi::X509_V_ERR_CERT_SIGNATURE_FAILURE => VerifyError::SignatureFailure,
+            ffi::X509_V_ERR_CERT_NOT_YET_VALID => VerifyError::NotYetValid,
+            ffi::X509_V_ERR_CERT_HAS_EXPIRED => VerifyError::Expired,
+            ffi::X509_V_ERR_CRL_NOT_YET_VALID => VerifyError::CrlNotYetValid,
+            ffi::X509_V_ERR_CRL_HAS_EXPIRED => VerifyError::CrlExpired,
+            ffi::X509_V_ERR_DEPTH_ZERO_SELF_SIGNED_CERT => VerifyError::SelfSignedLeaf,
+            ffi::X509_V_ERR_SELF_SIGNED_CERT_IN_CHAIN => VerifyError::UntrustedRoot,
+            ffi::X509_V_ERR_UNABLE_TO_GET_ISSUER_CERT_LOCALLY => {
+                VerifyError::UnableToGetIssuerLocally
+            }
+            ffi::X509_V_ERR_CERT_CHAIN_TOO_LONG => VerifyError::ChainTooLong,
+            ffi::X509_V_ERR_CERT_REVOKED => VerifyError::Revoked,
+            ffi::X509_V_ERR_INVALID_CA => VerifyError::InvalidCa,
+            ffi::X509_V_ERR_PATH_LENGTH_EXCEEDED => VerifyError::PathLengthExceeded,
+            ffi::X509_V_ERR_INVALID_PURPOSE => VerifyError::InvalidPurpose,
+            ffi::X509_V_ERR_CERT_UNTRUSTED => VerifyError::Untrusted,
+            ffi::X509_V_ERR_CERT_REJECTED => VerifyError::Rejected,
+            ffi::X509_V_ERR_HOSTNAME_MISMATCH => VerifyError::HostnameMismatch,
+            ffi::X509_V_ERR_EMAIL_MISMATCH => VerifyError::EmailMismatch,
+            ffi::X509_V_ERR_IP_ADDRESS_MISMATCH => VerifyError::IpAddressMismatch,
+            code => VerifyError::Other(code),
+        }
+    }
+
+    /// Returns the `X509_V_ERR_*` code of the error.
+    pub fn as_raw(&self) -> c_int {
+        match *self {
+            VerifyError::UnableToGetIssuer => ffi::X509_V_ERR_UNABLE_TO_GET_ISSUER_CERT,
+            VerifyError::UnableToGetCrl => ffi::X509_V_ERR_UNABLE_TO_GET_CRL,
+            VerifyError::SignatureFailure => ffi::X509_V_ERR_CERT_SIGNATURE_FAILURE,
+            VerifyError::NotYetValid => ffi::X509_V_ERR_CERT_NOT_YET_VALID,
+            VerifyError::Expired => ffi::X509_V_ERR_CERT_HAS_EXPIRED,
+            VerifyError::CrlNotYetValid => ffi::X509_V_ERR_CRL_NOT_YET_VALID,
+            VerifyError::CrlExpired => ffi::X509_V_ERR_CRL_HAS_EXPIRED,
+            VerifyError::SelfSignedLeaf => ffi::X509_V_ERR_DEPTH_ZERO_SELF_SIGNED_CERT,
+            VerifyError::UntrustedRoot => ffi::X509_V_ERR_SELF_SIGNED_CERT_IN_CHAIN,
+            VerifyError::UnableToGetIssuerLocally => {
+                ffi::X509_V_ERR_UNABLE_TO_GET_ISSUER_CERT_LOCALLY
+            }
+            VerifyError::ChainTooLong => ffi::X509_V_ERR_CERT_CHAIN_TOO_LONG,
+            VerifyError::Revoked => ffi::X509_V_ERR_CERT_REVOKED,
+            VerifyError::InvalidCa => ffi::X509_V_ERR_INVALID_CA,
+            VerifyError::PathLengthExceeded => ffi::X509_V_ERR_PATH_LENGTH_EXCEEDED,
+            VerifyError::InvalidPurpose => ffi::X509_V_ERR_INVALID_PURPOSE,
+            VerifyError::Untrusted => ffi::X509_V_ERR_CERT_UNTRUSTED,
+            VerifyError::Rejected => ffi::X509_V_ERR_CERT_REJECTED,
+            VerifyError::HostnameMismatch => ffi::X509_V_ERR_HOSTNAME_MISMATCH,
+            VerifyError::EmailMismatch => ffi::X509_V_ERR_EMAIL_MISMATCH,
+            VerifyError::IpAddressMismatch => ffi::X509_V_ERR_IP_ADDRESS_MISMATCH,
+            VerifyError::Other(code) => code,
+        }
+    }
+
+    /// Returns OpenSSL's human-readable description of the error.
+    ///
+    /// This is the message returned by `X509_verify_cert_error_string`, which is also used by
+    /// the `Display` implementation.
+    #[corresponds(X509_verify_cert_error_string)]
+    pub fn error_string(&self) -> &'static str {
+        match *self {
+            // Before 1.1.0, unknown codes are formatted into a static buffer, which is not
+            // thread safe.
+            #[cfg(not(ossl110))]
+            VerifyError::Other(_) => "unknown certificate verification error",
+            _ => unsafe { X509VerifyResult::from_raw(self.as_raw()) }.error_string(),
+        }
+    }
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            VerifyError::Other(code) => write!(fmt, "{} (code {})", self.error_string(), code),
+            _ => fmt.write_str(self.error_string()),
+        }
+    }
+}
+
+impl Error for VerifyError {}
+
+impl From<VerifyError> for io::Error {
+    fn from(e: VerifyError) -> io::Error {
+        io::Error::new(io::ErrorKind::InvalidData, e)
+    }
+}
+
+/// An error returned by [`verify_chain`].
+#[derive(Debug)]
+pub enum ChainError {
+    /// An error occurred while setting up verification.
+    ErrorStack(ErrorStack),
+    /// OpenSSL rejected the chain.
+    Verify {
+        /// The first error reported for the chain.
+        error: VerifyError,
+        /// The depth of the certificate the error was reported against, where the leaf is at
+        /// depth 0.
+        depth: usize,
+    },
+    /// A certificate in the chain has an RSA key smaller than the policy allows.
+    RsaKeyTooSmall {
+        /// The depth of the offending certificate, where the leaf is at depth 0.
+        depth: usize,
+        /// The size of the certificate's RSA key in bits.
+        bits: u32,
+    },
+    /// The leaf certificate does not list the extended key usage required by the policy.
+    MissingEku(Asn1Object),
+    /// The leaf certificate's key usage extension lacks the listed usages required by the policy.
+    MissingKeyUsage(X509KeyUsage),
+    /// The leaf certificate has no `dNSName` subject alternative name.
+    MissingDnsSan,
+    /// A stapled OCSP response does not contain a status for the leaf certificate.
+    MissingOcspStatus,
+    /// A DNS name in the leaf certificate violates the name constraints of the policy.
+    NameConstraint(String),
+    /// No certificate in the chain matches a pinned public key.
+    PinMismatch,
+    /// The chain contains more intermediate certificates than the policy allows.
+    TooManyIntermediates {
+        /// The number of intermediates, excluding the leaf and the trust anchor.
+        count: usize,
+    },
+    /// The leaf certificate's validity period is longer than the policy allows.
+    ValidityTooLong {
+        /// The length of the leaf's validity period in days, rounded up.
+        days: u32,
+    },
+    /// A certificate above the leaf does not assert `CA:TRUE` in its basicConstraints extension.
+    NotCa {
+        /// The depth of the offending certificate, where the leaf is at depth 0.
+        depth: usize,
+    },
+    /// A certificate in the chain is signed using SHA-1.
+    Sha1Signature {
+        /// The depth of the offending certificate, where the leaf is at depth 0.
+        depth: usize,
+    },
+    /// A certificate in the chain is signed using an algorithm the policy does not allow.
+    DisallowedSignatureAlgorithm {
+        /// The depth of the offending certificate, where the leaf is at depth 0.
+        depth: usize,
+        /// The certificate's signature algorithm.
+        nid: Nid,
+    },
+    /// A certificate's path length constraint does not allow the intermediates below it.
+    PathLenExceeded {
+        /// The depth of the offending certificate, where the leaf is at depth 0.
+        depth: usize,
+        /// The certificate's path length constraint.
+        pathlen: u32,
+    },
+    /// The bundle passed to [`verify_bundle`] contains no certificates.
+    EmptyBundle,
+    /// No certificate in the bundle or the trusted store issued a certificate in the bundle.
+    MissingIssuer {
+        /// The depth of the certificate whose issuer is missing, where the lowest intermediate of
+        /// the bundle is at depth 0.
+        depth: usize,
+        /// The issuer name of that certificate, formatted as comma-separated `name=value` entries.
+        issuer: String,
+    },
+}
+
+impl fmt::Display for ChainError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            ChainError::ErrorStack(ref e) => fmt::Display::fmt(e, fmt),
+            ChainError::Verify { ref error, depth } => write!(
+                fmt,
+                "certificate verification failed at depth {}: {}",
+                depth, error
+            ),
+            ChainError::RsaKeyTooSmall { depth, bits } => write!(
+                fmt,
+                "certificate at depth {} has a {}-bit RSA key, which is below the policy minimum",
+                depth, bits
+            ),
+            ChainError::MissingEku(ref oid) => write!(
+                fmt,
+                "leaf certificate does not have the required extended key usage {}",
+                &**oid
+            ),
+            ChainError::MissingKeyUsage(usage) => write!(
+                fmt,
+                "leaf certificate does not have the required key usage {:?}",
+                usage
+            ),
+            ChainError::MissingDnsSan => {
+                fmt.write_str("leaf certificate has no DNS subject alternative name")
+            }
+            ChainError::MissingOcspStatus => fmt.write_str(
+                "stapled OCSP response does not contain a status for the leaf certificate",
+            ),
+            ChainError::NameConstraint(ref name) => write!(
+                fmt,
+                "leaf certificate name {} violates the policy name constraints",
+                name
+            ),
+            ChainError::PinMismatch => {
+                fmt.write_str("no certificate in the chain matches a pinned public key")
+            }
+            ChainError::TooManyIntermediates { count } => write!(
+                fmt,
+                "chain contains {} intermediate certificates, which exceeds the policy maximum",
+                count
+            ),
+            ChainError::ValidityTooLong { days } => write!(
+                fmt,
+                "leaf certificate is valid for {} days, which exceeds the policy maximum",
+                days
+            ),
+            ChainError::NotCa { depth } => write!(
+                fmt,
+                "certificate at depth {} is not marked as a CA by basicConstraints",
+                depth
+            ),
+            ChainError::Sha1Signature { depth } => write!(
+                fmt,
+                "certificate at depth {} has a SHA-1 based signature",
+                depth
+            ),
+            ChainError::DisallowedSignatureAlgorithm { depth, nid } => write!(
+                fmt,
+                "certificate at depth {} is signed with {}, which the policy does not allow",
+                depth,
+                nid.long_name().unwrap_or("an unknown algorithm")
+            ),
+            ChainError::PathLenExceeded { depth, pathlen } => write!(
+                fmt,
+                "certificate at depth {} has a path length constraint of {}, which is exceeded",
+                depth, pathlen
+            ),
+            ChainError::EmptyBundle => fmt.write_str("the intermediate bundle is empty"),
+            ChainError::MissingIssuer { depth, ref issuer } => write!(
+                fmt,
+                "the issuer `{}` of the certificate at depth {} is missing from the bundle",
+                issuer, depth
+            ),
+        }
+    }
+}
+
+impl Error for ChainError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            ChainError::ErrorStack(ref e) => Some(e),
+            ChainError::Verify { ref error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<ErrorStack> for ChainError {
+    fn from(e: ErrorStack) -> ChainError {
+        ChainError::ErrorStack(e)
+    }
+}
+
+/// Verifies `leaf` against the trusted certificates in `roots`.
+///
+/// `intermediates` holds untrusted certificates which may be used to build the chain, and `param`
+/// is applied on top of the parameters already configured on `roots`. Once OpenSSL has
+/// successfully validated the chain, the checks configured in `policy` are run against it.
+pub fn verify_chain(
+    leaf: &X509Ref,
+    intermediates: &StackRef<X509>,
+    roots: &X509StoreRef,
+    param: &X509VerifyParamRef,
+    policy: &ChainPolicy,
+) -> Result<VerifiedChain, ChainError> {
+    let mut crls = Stack::new()?;
+    for crl in &policy.crls {
+        crls.push(crl.clone())?;
+    }
+
+    let mut context = X509StoreContext::new()?;
+    let result = context.init(roots, leaf, intermediates, |c| {
+        unsafe {
+            cvt(ffi::X509_VERIFY_PARAM_set1(
+                c.verify_param_mut().as_ptr(),
+                param.as_ptr(),
+            ))?;
+        }
+        if !crls.is_empty() {
+            unsafe { ffi::X509_STORE_CTX_set0_crls(c.as_ptr(), crls.as_ptr()) };
+            c.verify_param_mut().set_flags(X509VerifyFlags::CRL_CHECK)?;
+        }
+        if policy.untrusted_first {
+            c.verify_param_mut()
+                .clear_flags(X509VerifyFlags::TRUSTED_FIRST)?;
+        }
+        if !policy.allowed_errors.is_empty() {
+            unsafe {
+                let allowed = &policy.allowed_errors as *const Vec<VerifyError>;
+                cvt(ffi::X509_STORE_CTX_set_ex_data(
+                    c.as_ptr(),
+                    callback_data_idx()?,
+                    allowed as *mut c_void,
+                ))?;
+                ffi::X509_STORE_CTX_set_verify_cb(c.as_ptr(), Some(allow_errors_callback));
+            }
+        }
+
+        if !c.verify_cert()? {
+            return Ok(Err(ChainError::Verify {
+                error: VerifyError::from_raw(c.error().as_raw()),
+                depth: c.error_depth() as usize,
+            }));
+        }
+
+        let mut chain = Stack::new()?;
+        if let Some(verified) = c.chain() {
+            for cert in verified {
+                chain.push(cert.to_owned())?;
+            }
+        }
+
+        let mut shorter_path_available = false;
+        for cert in chain.iter().take(chain.len().saturating_sub(2)) {
+            let mut issuer = ptr::null_mut();
+            let found =
+                unsafe { ffi::X509_STORE_CTX_get1_issuer(&mut issuer, c.as_ptr(), cert.as_ptr()) };
+            if found < 0 {
+                return Err(ErrorStack::get());
+            }
+            if found > 0 {
+                unsafe { ffi::X509_free(issuer) };
+                shorter_path_available = true;
+                break;
+            }
+        }
+        Ok(Ok((chain, shorter_path_available)))
+    })?;
+    let (chain, shorter_path_available) = result?;
+
+    policy.check(&chain)?;
+
+    let anchor = chain
+        .get(chain.len() - 1)
+        .expect("verified chain is never empty");
+    let termination = termination(anchor);
+
+    Ok(VerifiedChain {
+        chain,
+        termination,
+        shorter_path_available,
+    })
+}
+
+fn termination(anchor: &X509Ref) -> ChainTermination {
+    let self_signed = anchor.issued(anchor) == X509VerifyResult::OK
+        && anchor
+            .public_key()
+            .and_then(|key| anchor.verify(&key))
+            .unwrap_or(false);
+    if self_signed {
+        ChainTermination::SelfSignedRoot
+    } else {
+        ChainTermination::TrustedIntermediate
+    }
+}
+
+/// Verifies `leaf` like [`verify_chain`], then checks the chain against a set of pinned keys.
+///
+/// Each pin is the SHA-256 hash of a DER-encoded SubjectPublicKeyInfo, as used by HTTP public key
+/// pinning. The chain is accepted if any certificate in it, including the trust anchor, matches
+/// any pin, so backup pins can be listed alongside the key currently in use. A chain which
+/// verifies but matches no pin is rejected with [`ChainError::PinMismatch`].
+pub fn verify_chain_with_pins(
+    leaf: &X509Ref,
+    intermediates: &StackRef<X509>,
+    roots: &X509StoreRef,
+    param: &X509VerifyParamRef,
+    pins: &[[u8; 32]],
+) -> Result<VerifiedChain, ChainError> {
+    let verified = verify_chain(leaf, intermediates, roots, param, &ChainPolicy::new())?;
+
+    for cert in verified.chain() {
+        let spki = cert.public_key()?.public_key_to_der()?;
+        let digest = hash(MessageDigest::sha256(), &spki)?;
+        if pins.iter().any(|pin| pin[..] == digest[..]) {
+            return Ok(verified);
+        }
+    }
+
+    Err(ChainError::PinMismatch)
+}
+
+/// Verifies the signing certificate of an RFC 3161 timestamp token like [`verify_chain`].
+///
+/// The leaf must list the `id-kp-timeStamping` extended key usage, or
+/// [`ChainError::MissingEku`] is returned before the chain is built. The chain is then verified
+/// for the [`X509PurposeId::TIMESTAMP_SIGN`] purpose, which overrides any purpose set on `param`
+/// and additionally requires the extension to be critical and to list no other usages, as
+/// RFC 3161 specifies.
+pub fn verify_tsa_chain(
+    leaf: &X509Ref,
+    intermediates: &StackRef<X509>,
+    roots: &X509StoreRef,
+    param: &X509VerifyParamRef,
+) -> Result<VerifiedChain, ChainError> {
+    let oid = Asn1Object::from_str(TIME_STAMPING_OID)?;
+    if !has_eku(leaf, &oid) {
+        return Err(ChainError::MissingEku(oid));
+    }
+
+    let mut param = param.to_owned()?;
+    param.set_purpose(X509PurposeId::TIMESTAMP_SIGN)?;
+    verify_chain(leaf, intermediates, roots, &param, &ChainPolicy::new())
+}
+
+const TIME_STAMPING_OID: &str = "1.3.6.1.5.5.7.3.8";
+
+/// Verifies a TLS client certificate like [`verify_chain`] and returns the leaf's subject.
+///
+/// The leaf must list the `id-kp-clientAuth` extended key usage, or [`ChainError::MissingEku`] is
+/// returned before the chain is built, so server-only certificates are rejected. The chain is then
+/// verified for the [`X509PurposeId::SSL_CLIENT`] purpose, which overrides any purpose set on
+/// `param`. On success, the leaf's subject is returned as comma-separated `key=value` pairs, such
+/// as `CN=alice, O=Example`.
+pub fn verify_client_cert(
+    cert: &X509Ref,
+    intermediates: &StackRef<X509>,
+    roots: &X509StoreRef,
+    param: &X509VerifyParamRef,
+) -> Result<String, ChainError> {
+    let oid = Asn1Object::from_str(CLIENT_AUTH_OID)?;
+    if !has_eku(cert, &oid) {
+        return Err(ChainError::MissingEku(oid));
+    }
+
+    let mut param = param.to_owned()?;
+    param.set_purpose(X509PurposeId::SSL_CLIENT)?;
+    let verified = verify_chain(cert, intermediates, roots, &param, &ChainPolicy::new())?;
+    Ok(format_name(verified.leaf().subject_name()))
+}
+
+const CLIENT_AUTH_OID: &str = "1.3.6.1.5.5.7.3.2";
+
+/// The signer of a code-signing chain verified by [`verify_code_signing`].
+pub struct CodeSigner {
+    subject: String,
+    chain: VerifiedChain,
+}
+
+impl CodeSigner {
+    /// Returns the signer's subject as comma-separated `key=value` pairs, such as `CN=Example`.
+    pub fn subject(&self) -> &str {
+        &self.subject
+    }
+
+    /// Returns the start of the signing certificate's validity period.
+    pub fn not_before(&self) -> &Asn1TimeRef {
+        self.chain.not_before()
+    }
+
+    /// Returns the end of the signing certificate's validity period.
+    ///
+    /// A signature made after this time should only be accepted if it carries a trusted
+    /// timestamp from within the validity period.
+    pub fn not_after(&self) -> &Asn1TimeRef {
+        self.chain.not_after()
+    }
+
+    /// Returns the verified chain.
+    pub fn chain(&self) -> &VerifiedChain {
+        &self.chain
+    }
+}
+
+/// Verifies a code-signing certificate like [`verify_chain`].
+///
+/// The leaf must list the `id-kp-codeSigning` extended key usage, or [`ChainError::MissingEku`]
+/// is returned before the chain is built, so TLS and other certificates are rejected. On success,
+/// the signer's subject and validity period are returned.
+pub fn verify_code_signing(
+    cert: &X509Ref,
+    intermediates: &StackRef<X509>,
+    roots: &X509StoreRef,
+    param: &X509VerifyParamRef,
+) -> Result<CodeSigner, ChainError> {
+    let oid = Asn1Object::from_str(CODE_SIGNING_OID)?;
+    if !has_eku(cert, &oid) {
+        return Err(ChainError::MissingEku(oid));
+    }
+
+    let chain = verify_chain(cert, intermediates, roots, param, &ChainPolicy::new())?;
+    Ok(CodeSigner {
+        subject: format_name(chain.leaf().subject_name()),
+        chain,
+    })
+}
+
+const CODE_SIGNING_OID: &str = "1.3.6.1.5.5.7.3.3";
+
+/// Checks that a bundle of intermediate certificates builds a chain up to a trusted root.
+///
+/// This validates a server's configured intermediates without a leaf, catching bundles which
+/// are missing an intermediate. The order of `intermediates` does not matter: every bottom-most
+/// intermediate, which issued no other certificate in the bundle, is verified against `roots`
+/// with the rest of the bundle as untrusted certificates, so a bundle which falls apart into
+/// several pieces is rejected. The longest of the verified chains is returned.
+///
+/// A gap in the chain is reported as [`ChainError::MissingIssuer`], naming the issuer which
+/// could not be found. Other failures are reported as by [`verify_chain`].
+pub fn verify_bundle(
+    intermediates: &StackRef<X509>,
+    roots: &X509StoreRef,
+    param: &X509VerifyParamRef,
+) -> Result<VerifiedChain, ChainError> {
+    let find_issuer = |cert: &X509Ref| {
+        intermediates.iter().find(|&issuer| {
+            issuer.as_ptr() != cert.as_ptr() && issuer.issued(cert) == X509VerifyResult::OK
+        })
+    };
+    let mut bottoms = intermediates
+        .iter()
+        .filter(|&cert| {
+            !intermediates.iter().any(|other| {
+                other.as_ptr() != cert.as_ptr() && cert.issued(other) == X509VerifyResult::OK
+            })
+        })
+        .collect::<Vec<_>>();
+    if bottoms.is_empty() {
+        bottoms.extend(intermediates.iter().next());
+    }
+    if bottoms.is_empty() {
+        return Err(ChainError::EmptyBundle);
+    }
+
+    let mut longest: Option<VerifiedChain> = None;
+    for bottom in bottoms {
+        let chain = match verify_chain(bottom, intermediates, roots, param, &ChainPolicy::new()) {
+            Ok(chain) => chain,
+            Err(ChainError::Verify {
+                error: VerifyError::UnableToGetIssuer | VerifyError::UnableToGetIssuerLocally,
+                depth,
+            }) => {
+                let mut cert = bottom;
+                for _ in 0..depth {
+                    match find_issuer(cert) {
+                        Some(issuer) => cert = issuer,
+                        None => break,
+                    }
+                }
+                return Err(ChainError::MissingIssuer {
+                    depth,
+                    issuer: format_name(cert.issuer_name()),
+                });
+            }
+            Err(e) => return Err(e),
+        };
+        if longest
+            .as_ref()
+            .map_or(true, |l| chain.chain().len() > l.chain().len())
+        {
+            longest = Some(chain);
+        }
+    }
+
+    Ok(longest.unwrap())
+}
+
+/// A diagnostic report produced by [`verify_chain_report`].
+#[derive(Debug, Clone)]
+pub struct VerifyReport {
+    /// The overall result, which is the first error reported by OpenSSL if verification failed.
+    pub result: Result<(), VerifyError>,
+    /// The certificates of the constructed chain, ordered from the leaf towards the trust anchor.
+    pub certificates: Vec<CertificateReport>,
+    /// The name which matched the host set on the parameters, if any.
+    ///
+    /// This is always `None` before OpenSSL 1.1.0.
+    pub matched_host: Option<String>,
+    /// How the chain ends, or `None` if it could not be built up to a trusted certificate.
+    pub termination: Option<ChainTermination>,
+}
+
+/// A certificate in a [`VerifyReport`].
+#[derive(Debug, Clone)]
+pub struct CertificateReport {
+    /// The subject name, formatted as comma-separated `name=value` entries.
+    pub subject: String,
+    /// The issuer name, formatted like the subject.
+    pub issuer: String,
+    /// The start of the validity period.
+    pub not_before: String,
+    /// The end of the validity period.
+    pub not_after: String,
+    /// The errors reported by OpenSSL for the certificate at this depth.
+    pub errors: Vec<VerifyError>,
+}
+
+/// Verifies `leaf` like [`verify_chain`], but collects a diagnostic report instead of stopping at
+/// the first error.
+///
+/// Verification continues past every error so that the whole chain is examined, and each error
+/// is recorded against the depth at which OpenSSL reported it. The report is meant for audit and
+/// troubleshooting tools; [`VerifyReport::result`] must be checked before trusting the chain. An
+/// error is only returned if verification could not be performed at all.
+///
+/// Errors are collected by a verify callback installed on the verification context, which
+/// replaces any verify callback configured on `roots`.
+pub fn verify_chain_report(
+    leaf: &X509Ref,
+    intermediates: &StackRef<X509>,
+    roots: &X509StoreRef,
+    param: &X509VerifyParamRef,
+) -> Result<VerifyReport, ErrorStack> {
+    let mut errors: Vec<(usize, VerifyError)> = vec![];
+
+    let mut context = X509StoreContext::new()?;
+    let (chain, matched_host) = context.init(roots, leaf, intermediates, |c| {
+        unsafe {
+            cvt(ffi::X509_VERIFY_PARAM_set1(
+                c.verify_param_mut().as_ptr(),
+                param.as_ptr(),
+            ))?;
+            cvt(ffi::X509_STORE_CTX_set_ex_data(
+                c.as_ptr(),
+                callback_data_idx()?,
+                &mut errors as *mut Vec<(usize, VerifyError)> as *mut c_void,
+            ))?;
+            ffi::X509_STORE_CTX_set_verify_cb(c.as_ptr(), Some(record_errors_callback));
+        }
+        c.verify_cert()?;
+
+        let chain = c
+            .chain()
+            .map(|chain| chain.iter().map(|cert| cert.to_owned()).collect::<Vec<_>>())
+            .unwrap_or_default();
+        #[cfg(ossl110)]
+        let matched_host = unsafe {
+            let peername = ffi::X509_VERIFY_PARAM_get0_peername(c.verify_param_mut().as_ptr());
+            if peername.is_null() {
+                None
+            } else {
+                Some(CStr::from_ptr(peername).to_string_lossy().into_owned())
+            }
+        };
+        #[cfg(not(ossl110))]
+        let matched_host = None;
+        Ok((chain, matched_host))
+    })?;
+
+    let result = match errors.first() {
+        Some(&(_, error)) => Err(error),
+        None => Ok(()),
+    };
+    let anchored = !errors.iter().any(|&(_, error)| {
+        matches!(
+            error,
+            VerifyError::UnableToGetIssuer
+                | VerifyError::UnableToGetIssuerLocally
+                | VerifyError::SelfSignedLeaf
+                | VerifyError::UntrustedRoot
+        )
+    });
+    let termination = match chain.last() {
+        Some(anchor) if anchored => Some(termination(anchor)),
+        _ => None,
+    };
+    let certificates = chain
+        .iter()
+        .enumerate()
+        .map(|(depth, cert)| CertificateReport {
+            subject: format_name(cert.subject_name()),
+            issuer: format_name(cert.issuer_name()),
+            not_before: cert.not_before().to_string(),
+            not_after: cert.not_after().to_string(),
+            errors: errors
+                .iter()
+                .filter(|&&(d, _)| d == depth)
+                .map(|&(_, error)| error)
+                .collect(),
+        })
+        .collect();
+
+    Ok(VerifyReport {
+        result,
+        certificates,
+        matched_host,
+        termination,
+    })
+}
+
+fn format_name(name: &X509NameRef) -> String {
+    name.entries()
+        .map(|entry| {
+            let nid = entry.object().nid();
+            let key = nid.short_name().unwrap_or("UNDEF");
+            match entry.data().as_utf8() {
+                Ok(value) => format!("{}={}", key, value),
+                Err(_) => format!("{}=<invalid>", key),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+static CALLBACK_DATA_IDX: OnceCell<c_int> = OnceCell::new();
+
+/// Returns the ex_data index holding the data of the verify callbacks installed here.
+fn callback_data_idx() -> Result<c_int, ErrorStack> {
+    CALLBACK_DATA_IDX
+        .get_or_try_init(|| unsafe {
+            ffi::init();
+            cvt_n(ffi::X509_STORE_CTX_get_ex_new_index(
+                0,
+                ptr::null_mut(),
+                None,
+                None,
+                None,
+            ))
+        })
+        .copied()
+}
+
+/// Returns the data stored for a verify callback, or null if there is none.
+unsafe fn callback_data(ctx: *mut ffi::X509_STORE_CTX) -> *mut c_void {
+    match CALLBACK_DATA_IDX.get() {
+        Some(&idx) => ffi::X509_STORE_CTX_get_ex_data(ctx, idx),
+        None => ptr::null_mut(),
+    }
+}
+
+extern "C" fn record_errors_callback(ok: c_int, ctx: *mut ffi::X509_STORE_CTX) -> c_int {
+    if ok == 0 {
+        unsafe {
+            let errors = callback_data(ctx) as *mut Vec<(usize, VerifyError)>;
+            let depth = ffi::X509_STORE_CTX_get_error_depth(ctx) as usize;
+            let error = VerifyError::from_raw(ffi::X509_STORE_CTX_get_error(ctx));
+            if let Some(errors) = errors.as_mut() {
+                errors.push((depth, error));
+            }
+        }
+    }
+    1
+}
+
+extern "C" fn allow_errors_callback(ok: c_int, ctx: *mut ffi::X509_STORE_CTX) -> c_int {
+    if ok != 0 {
+        return ok;
+    }
+    unsafe {
+        let allowed = callback_data(ctx) as *const Vec<VerifyError>;
+        let error = VerifyError::from_raw(ffi::X509_STORE_CTX_get_error(ctx));
+        match allowed.as_ref() {
+            Some(allowed) if allowed.contains(&error) => 1,
+            _ => ok,
+        }
+    }
+}
+
+/// Checks whether `cert` is valid for the DNS name `name` without using OpenSSL's matcher.
+///
+/// Matching follows RFC 6125 and behaves the same on every OpenSSL version:
+///
+/// * Names are compared case-insensitively, and a single trailing dot is ignored on both sides.
+/// * A wildcard is only recognized in the left-most label of a DNS subject alternative name and
+///   matches exactly one non-empty label. It must be followed by at least two labels, so
+///   `*.com` matches nothing, and partial wildcards such as `b*z.example.net` never match an
+///   IDNA `xn--` label.
+/// * The subject common name is only consulted if the certificate has no DNS subject
+///   alternative names.
+/// * IP address literals never match; use [`X509VerifyParamRef::set_ip`] for those.
+///
+/// `flags` supports [`X509CheckFlags::NO_WILDCARDS`], [`X509CheckFlags::NO_PARTIAL_WILDCARDS`],
+/// [`X509CheckFlags::ALWAYS_CHECK_SUBJECT`] and `X509CheckFlags::NEVER_CHECK_SUBJECT`; other
+/// flags are ignored.
+pub fn check_host_rust(cert: &X509Ref, name: &str, flags: X509CheckFlags) -> bool {
+    if name.parse::<IpAddr>().is_ok() {
+        return false;
+    }
+    let name = name.strip_suffix('.').unwrap_or(name);
+    if name.is_empty() {
+        return false;
+    }
+
+    let mut has_dns_san = false;
+    if let Some(sans) = cert.subject_alt_names() {
+        for dns in sans.iter().filter_map(|san| san.dnsname()) {
+            has_dns_san = true;
+            if host_matches(dns, name, flags) {
+                return true;
+            }
+        }
+    }
+
+    #[cfg(ossl110)]
+    let never_check_subject = flags.contains(X509CheckFlags::NEVER_CHECK_SUBJECT);
+    #[cfg(not(ossl110))]
+    let never_check_subject = false;
+    if never_check_subject || (has_dns_san && !flags.contains(X509CheckFlags::ALWAYS_CHECK_SUBJECT))
+    {
+        return false;
+    }
+
+    cert.subject_name()
+        .entries_by_nid(Nid::COMMONNAME)
+        .filter_map(|cn| cn.data().as_utf8().ok())
+        .any(|cn| host_matches(&cn, name, flags))
+}
+
+fn host_matches(pattern: &str, name: &str, flags: X509CheckFlags) -> bool {
+    let pattern = pattern.strip_suffix('.').unwrap_or(pattern);
+    if !pattern.contains('*') {
+        return pattern.eq_ignore_ascii_case(name);
+    }
+    if flags.contains(X509CheckFlags::NO_WILDCARDS) {
+        return false;
+    }
+
+    let (wildcard, pattern_rest) = match pattern.split_once('.') {
+        Some(parts) => parts,
+        None => return false,
+    };
+    let (label, name_rest) = match name.split_once('.') {
+        Some(parts) => parts,
+        None => return false,
+    };
+    if pattern_rest.contains('*')
+        || !pattern_rest.contains('.')
+        || !pattern_rest.eq_ignore_ascii_case(name_rest)
+    {
+        return false;
+    }
+
+    let (prefix, suffix) = match wildcard.split_once('*') {
+        Some(parts) => parts,
+        None => return false,
+    };
+    if suffix.contains('*') || label.is_empty() {
+        return false;
+    }
+    if prefix.is_empty() && suffix.is_empty() {
+        return true;
+    }
+    if flags.contains(X509CheckFlags::NO_PARTIAL_WILDCARDS)
+        || wildcard
+            .get(..4)
+            .is_some_and(|p| p.eq_ignore_ascii_case("xn--"))
+        || label
+            .get(..4)
+            .is_some_and(|p| p.eq_ignore_ascii_case("xn--"))
+    {
+        return false;
+    }
+
+    label.len() >= prefix.len() + suffix.len()
+        && label
+            .get(..prefix.len())
+            .is_some_and(|p| p.eq_ignore_ascii_case(prefix))
+        && label
+            .get(label.len() - suffix.len()..)
+            .is_some_and(|s| s.eq_ignore_ascii_case(suffix))
+}